@@ -0,0 +1,75 @@
+//! Replays a generated day (in 10 seconds) while random config mutations are hot reloaded mid-run, then checks that every
+//! decision was made against a single, consistent config revision. The config is mutated on a scratch copy.
+//!
+//! `cargo run --bin chaos_day -- validator_config.xml 42`
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use validation_state_engine::library::clock::AcceleratedClock;
+use validation_state_engine::library::configuration_service::ConfigurationService;
+use validation_state_engine::library::file_watcher::WatchOptions;
+use validation_state_engine::library::Result;
+use validation_state_engine::simulation::{
+    chaos, ChaosOptions, ChaosReport, Workload, WorkloadOptions,
+};
+
+async fn chaos_day(config_path: &str, seed: u64) -> Result<ChaosReport> {
+    let file_name = Path::new(config_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let scratch = std::env::temp_dir().join(format!("chaos_{}_{file_name}", std::process::id()));
+    std::fs::copy(config_path, &scratch)?;
+
+    let workload = Workload::generate(&WorkloadOptions {
+        seed,
+        ..Default::default()
+    });
+    let options = ChaosOptions {
+        seed,
+        wait_for_reload: false,
+        realtime: true,
+        ..Default::default()
+    };
+    // The day in 10 seconds
+    let clock = AcceleratedClock::new(workload.day.and_time(NaiveTime::MIN), 86_400 / 10);
+    let watch_options = WatchOptions {
+        debounce: Duration::from_secs(60),
+        clock: Arc::new(clock),
+        ..Default::default()
+    };
+    let config_service =
+        ConfigurationService::with_options(scratch.to_string_lossy().to_string(), watch_options)
+            .await;
+    let report = chaos(config_service, &workload, &options).await;
+    std::fs::remove_file(&scratch)?;
+    report
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let config_path = args.next().unwrap_or("validator_config.xml".to_string());
+    let Ok(seed) = args.next().map_or(Ok(42), |seed| seed.parse::<u64>()) else {
+        eprintln!("Usage: chaos_day [config] [seed]");
+        return ExitCode::FAILURE;
+    };
+
+    match chaos_day(&config_path, seed).await {
+        Ok(report) => {
+            print!("{report}");
+            if report.is_consistent() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("--> Chaos ERROR: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Translates a rule config between XML, TOML, JSON and YAML, formats are picked by file extension.
+//!
+//! `cargo run --bin convert_config -- validator_config.xml validator_config.toml`
+use std::path::Path;
+use std::process::ExitCode;
+
+use validation_state_engine::config::prelude::ConfigFormat;
+use validation_state_engine::library::Result;
+
+fn convert(input: &Path, output: &Path) -> Result<()> {
+    let (from, to) = (
+        ConfigFormat::from_path(input)?,
+        ConfigFormat::from_path(output)?,
+    );
+    let config = from.parse(&std::fs::read_to_string(input)?)?;
+    std::fs::write(output, to.write(&config)?)?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [input, output] = args.as_slice() else {
+        eprintln!("Usage: convert_config <input> <output>   (.xml, .toml, .json, .yaml, .yml)");
+        return ExitCode::FAILURE;
+    };
+
+    match convert(Path::new(input), Path::new(output)) {
+        Ok(()) => {
+            println!("Converted {input} -> {output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("--> Config ERROR: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Writes the XML Schema and the JSON Schema of the rule config into a directory (`schema/` by default).
+//!
+//! `cargo run --bin export_schema -- schema`
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use validation_state_engine::config::schema;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let dir = match args.as_slice() {
+        [] => PathBuf::from("schema"),
+        [dir] if !dir.starts_with('-') => PathBuf::from(dir),
+        _ => {
+            eprintln!("Usage: export_schema [dir]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let written = std::fs::create_dir_all(&dir).and_then(|_| {
+        std::fs::write(dir.join("validator_config.xsd"), schema::xml_schema())?;
+        std::fs::write(
+            dir.join("validator_config.schema.json"),
+            schema::json_schema(),
+        )
+    });
+
+    match written {
+        Ok(()) => {
+            println!("Schemas written to {}", dir.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("--> Schema export ERROR: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
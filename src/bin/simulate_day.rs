@@ -0,0 +1,50 @@
+//! Replays a generated day of booking activity against a rule config and prints the per hour report.
+//!
+//! `cargo run --bin simulate_day -- validator_config.xml 42`
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use chrono::NaiveTime;
+use validation_state_engine::library::clock::{FixedClock, SharedClock};
+use validation_state_engine::library::configuration_service::ConfigurationService;
+use validation_state_engine::library::file_watcher::WatchOptions;
+use validation_state_engine::library::rule_validation_service::RuleValidationService;
+use validation_state_engine::simulation::{Simulation, Workload, WorkloadOptions};
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let config_path = args.next().unwrap_or("validator_config.xml".to_string());
+    let Ok(seed) = args.next().map_or(Ok(42), |seed| seed.parse::<u64>()) else {
+        eprintln!("Usage: simulate_day [config] [seed]");
+        return ExitCode::FAILURE;
+    };
+
+    let options = WorkloadOptions {
+        seed,
+        ..Default::default()
+    };
+    let workload = Workload::generate(&options);
+
+    // One clock for the simulation and the services
+    let clock: SharedClock = Arc::new(FixedClock::new(options.day.and_time(NaiveTime::MIN)));
+    let watch_options = WatchOptions {
+        clock: clock.clone(),
+        ..Default::default()
+    };
+    let config_service = ConfigurationService::with_options(config_path, watch_options).await;
+    let service = RuleValidationService::new(config_service).await;
+    let mut simulation = Simulation::with_clock(service, options.day, clock);
+    workload.add_to(&mut simulation);
+
+    match simulation.run(workload.events) {
+        Ok(report) => {
+            print!("{report}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("--> Simulation ERROR: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
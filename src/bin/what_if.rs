@@ -0,0 +1,52 @@
+//! Replays the same generated day against a baseline and a candidate config and prints every decision that flips.
+//!
+//! `cargo run --bin what_if -- validator_config.xml validator_config.new.xml 42`
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use validation_state_engine::config::prelude::ConfigFormat;
+use validation_state_engine::library::Result;
+use validation_state_engine::simulation::{
+    compare, NamedConfig, WhatIfReport, Workload, WorkloadOptions,
+};
+
+fn load(path: &str) -> Result<NamedConfig> {
+    let source = PathBuf::from(path);
+    let config = ConfigFormat::from_path(&source)?.parse(&std::fs::read_to_string(&source)?)?;
+    Ok(NamedConfig { source, config })
+}
+
+fn what_if(baseline: &str, candidate: &str, seed: u64) -> Result<WhatIfReport> {
+    let workload = Workload::generate(&WorkloadOptions {
+        seed,
+        ..Default::default()
+    });
+    compare(load(baseline)?, load(candidate)?, &workload)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (baseline, candidate, seed) = match args.as_slice() {
+        [baseline, candidate] => (baseline, candidate, Ok(42)),
+        [baseline, candidate, seed] => (baseline, candidate, seed.parse::<u64>()),
+        _ => {
+            eprintln!("Usage: what_if <baseline> <candidate> [seed]");
+            return ExitCode::FAILURE;
+        }
+    };
+    let Ok(seed) = seed else {
+        eprintln!("Usage: what_if <baseline> <candidate> [seed]");
+        return ExitCode::FAILURE;
+    };
+
+    match what_if(baseline, candidate, seed) {
+        Ok(report) => {
+            print!("{report}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("--> What-if ERROR: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,106 +1,798 @@
+use super::schedule::{fmt_optional_datetime, parse_optional_datetime, Schedule, TimeWindow};
+use crate::handlers::rule_definitions::*;
 use crate::library::RuleType;
 
+use chrono::NaiveDateTime;
+
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::se::Serializer as XmlSerializer;
+use quick_xml::{DeError, Reader, SeError};
 use serde::de::{Deserializer, Error};
-use serde::Deserialize;
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::num::ParseIntError;
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
     #[serde(rename = "ValidationRules")]
     pub validation_rules: ValidationRulesContainer,
+    /// Attributes of the XML root the engine doesn't use (e.g. `xsi:noNamespaceSchemaLocation`), written back
+    /// by `to_xml`. Not part of the rule set: ignored by `==` and not carried over to the other `ConfigFormat`s.
+    #[serde(skip)]
+    pub root_attributes: Vec<(String, String)>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+/// Only the rule set counts, see `Config::root_attributes`.
+impl PartialEq for Config {
+    fn eq(&self, other: &Self) -> bool {
+        self.validation_rules == other.validation_rules
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct ValidationRulesContainer {
     #[serde(rename = "Groups")]
     pub groups: GroupsContainer,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct GroupsContainer {
     #[serde(rename = "ValidationRulesGroup")]
     pub validation_rules_groups: Vec<ValidationRulesGroupSettings>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct ValidationRulesGroupSettings {
-    #[serde(rename = "@Group")]
+    #[serde(rename = "@Group", alias = "Group")]
     pub group: String,
     #[serde(rename = "ValidationRule")]
     pub validation_rules: Vec<ValidationRuleSettings>,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(from = "RawValidationRuleSettings")]
 pub struct ValidationRuleSettings {
-    #[serde(rename = "@Type")]
     pub rule_type: RuleType,
-    #[serde(rename = "@Enabled", deserialize_with = "parse_bool")]
     pub enabled: bool,
-    #[serde(rename = "@FallbackShiftStatusId", default)]
     pub fallback_shift_status_id: Option<i32>,
+    pub position_type_ids: Vec<i32>,
+    pub from_match_status_id: Option<i32>,
+    pub schedule: Schedule,
+    pub rules: RulesContainer,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RulesContainer {
+    #[serde(rename = "Rule")]
+    pub rules: Vec<Rule>,
+}
+
+impl RulesContainer {
+    /// `<Rule>` entry that applies to the candidate status. An explicit status id match wins over
+    /// an entry with empty `ForCandidateStatusIds` (which applies to every status).
+    pub fn for_candidate_status(&self, status_id: i32) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.for_candidate_status_ids().contains(&status_id))
+            .or_else(|| {
+                self.rules
+                    .iter()
+                    .find(|rule| rule.for_candidate_status_ids().is_empty())
+            })
+    }
+}
+
+/// Single `<Rule>` entry, typed by the `Type` of its parent `<ValidationRule>`. </br>
+/// Rule level parameters that live on the parent (e.g. `PositionTypeIDs`) are copied in,
+/// so each evaluator receives everything it needs from one struct.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    SideJobPrevention(SideJobPrevention),
+    IndecisivePrevention(IndecisivePrevention),
+    ExhaustionPrevention(ExhaustionPrevention),
+    LastMinuteActionPreventionForBooking(LastMinuteActionPreventionForBooking),
+    LastMinuteActionPreventionForCanceling(LastMinuteActionPreventionForCanceling),
+}
+
+impl Rule {
+    pub fn for_candidate_status_ids(&self) -> &[i32] {
+        match self {
+            Rule::SideJobPrevention(rule) => &rule.for_candidate_status_ids,
+            Rule::IndecisivePrevention(rule) => &rule.for_candidate_status_ids,
+            Rule::ExhaustionPrevention(rule) => &rule.for_candidate_status_ids,
+            Rule::LastMinuteActionPreventionForBooking(rule) => &rule.for_candidate_status_ids,
+            Rule::LastMinuteActionPreventionForCanceling(rule) => &rule.for_candidate_status_ids,
+        }
+    }
+
+    /// Empty `Enforce=""` is treated as not enforced.
+    pub fn enforce(&self) -> bool {
+        let enforce = match self {
+            Rule::SideJobPrevention(rule) => rule.enforce,
+            Rule::IndecisivePrevention(rule) => rule.enforce,
+            Rule::ExhaustionPrevention(rule) => rule.enforce,
+            Rule::LastMinuteActionPreventionForBooking(rule) => rule.enforce,
+            Rule::LastMinuteActionPreventionForCanceling(rule) => rule.enforce,
+        };
+        enforce.unwrap_or(false)
+    }
+
+    /// Rule of `rule_type` with nothing configured.
+    pub fn empty(rule_type: &RuleType) -> Self {
+        match rule_type {
+            RuleType::SideJobPrevention => Rule::SideJobPrevention(Default::default()),
+            RuleType::IndecisivePrevention => Rule::IndecisivePrevention(Default::default()),
+            RuleType::ExhaustionPrevention => Rule::ExhaustionPrevention(Default::default()),
+            RuleType::LastMinuteActionPreventionForBooking => {
+                Rule::LastMinuteActionPreventionForBooking(Default::default())
+            }
+            RuleType::LastMinuteActionPreventionForCanceling => {
+                Rule::LastMinuteActionPreventionForCanceling(Default::default())
+            }
+        }
+    }
+
+    /// `<ValidationRule>` attributes copied into this rule, see `RawValidationRuleSettings::typed_rule`.
+    pub fn inherited_attributes(&self) -> &'static [&'static str] {
+        match self {
+            Rule::SideJobPrevention(_) => &["PositionTypeIDs", "FromMatchStatusId"],
+            Rule::ExhaustionPrevention(_) => &["FallbackShiftStatusId"],
+            Rule::IndecisivePrevention(_)
+            | Rule::LastMinuteActionPreventionForBooking(_)
+            | Rule::LastMinuteActionPreventionForCanceling(_) => &[],
+        }
+    }
+
+    pub fn rule_type(&self) -> RuleType {
+        match self {
+            Rule::SideJobPrevention(_) => RuleType::SideJobPrevention,
+            Rule::IndecisivePrevention(_) => RuleType::IndecisivePrevention,
+            Rule::ExhaustionPrevention(_) => RuleType::ExhaustionPrevention,
+            Rule::LastMinuteActionPreventionForBooking(_) => {
+                RuleType::LastMinuteActionPreventionForBooking
+            }
+            Rule::LastMinuteActionPreventionForCanceling(_) => {
+                RuleType::LastMinuteActionPreventionForCanceling
+            }
+        }
+    }
+
+    /// Sets a `<Rule>` attribute from its XML text, the counterpart of `attributes()`.
+    pub fn set_attribute(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let rule_type = self.rule_type();
+        match (self, name) {
+            (Rule::SideJobPrevention(rule), "ForCandidateStatusIds") => {
+                rule.for_candidate_status_ids = ids_value(name, value)?
+            }
+            (Rule::SideJobPrevention(rule), "Enforce") => rule.enforce = bool_value(name, value)?,
+            (Rule::IndecisivePrevention(rule), "ForCandidateStatusIds") => {
+                rule.for_candidate_status_ids = ids_value(name, value)?
+            }
+            (Rule::IndecisivePrevention(rule), "IfShiftEndReasonIds") => {
+                rule.if_shift_end_reason_ids = ids_value(name, value)?
+            }
+            (Rule::IndecisivePrevention(rule), "Enforce") => {
+                rule.enforce = bool_value(name, value)?
+            }
+            (Rule::IndecisivePrevention(rule), "ForTheNextXDays") => {
+                rule.for_the_next_x_days = i32_value(name, value)?
+            }
+            (Rule::ExhaustionPrevention(rule), "ForCandidateStatusIds") => {
+                rule.for_candidate_status_ids = ids_value(name, value)?
+            }
+            (Rule::ExhaustionPrevention(rule), "ForShiftStatusIds") => {
+                rule.for_shift_status_ids = ids_value(name, value)?
+            }
+            (Rule::ExhaustionPrevention(rule), "HoursAllowed") => {
+                rule.hours_allowed = i32_value(name, value)?
+            }
+            (Rule::ExhaustionPrevention(rule), "WithinXHours") => {
+                rule.within_x_hours = i32_value(name, value)?
+            }
+            (Rule::ExhaustionPrevention(rule), "Enforce") => {
+                rule.enforce = bool_value(name, value)?
+            }
+            (Rule::LastMinuteActionPreventionForBooking(rule), "ForCandidateStatusIds") => {
+                rule.for_candidate_status_ids = ids_value(name, value)?
+            }
+            (Rule::LastMinuteActionPreventionForBooking(rule), "Minutes") => {
+                rule.minutes = i32_value(name, value)?
+            }
+            (Rule::LastMinuteActionPreventionForBooking(rule), "Enforce") => {
+                rule.enforce = bool_value(name, value)?
+            }
+            (Rule::LastMinuteActionPreventionForCanceling(rule), "ForCandidateStatusIds") => {
+                rule.for_candidate_status_ids = ids_value(name, value)?
+            }
+            (Rule::LastMinuteActionPreventionForCanceling(rule), "Minutes") => {
+                rule.minutes = i32_value(name, value)?
+            }
+            (Rule::LastMinuteActionPreventionForCanceling(rule), "Enforce") => {
+                rule.enforce = bool_value(name, value)?
+            }
+            _ => {
+                return Err(format!(
+                    "Unknown attribute '{name}' for {rule_type:?} <Rule>"
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// `<Rule>` attributes as written in the XML, unset values are empty strings.
+    pub fn attributes(&self) -> Vec<(&'static str, String)> {
+        let ids = |ids: &[i32]| ("ForCandidateStatusIds", fmt_csv(ids));
+        match self {
+            Rule::SideJobPrevention(rule) => vec![
+                ids(&rule.for_candidate_status_ids),
+                ("Enforce", fmt_optional_bool(rule.enforce)),
+            ],
+            Rule::IndecisivePrevention(rule) => vec![
+                ids(&rule.for_candidate_status_ids),
+                (
+                    "IfShiftEndReasonIds",
+                    fmt_csv(&rule.if_shift_end_reason_ids),
+                ),
+                ("Enforce", fmt_optional_bool(rule.enforce)),
+                ("ForTheNextXDays", fmt_optional(rule.for_the_next_x_days)),
+            ],
+            Rule::ExhaustionPrevention(rule) => vec![
+                ids(&rule.for_candidate_status_ids),
+                ("ForShiftStatusIds", fmt_csv(&rule.for_shift_status_ids)),
+                ("HoursAllowed", fmt_optional(rule.hours_allowed)),
+                ("WithinXHours", fmt_optional(rule.within_x_hours)),
+                ("Enforce", fmt_optional_bool(rule.enforce)),
+            ],
+            Rule::LastMinuteActionPreventionForBooking(rule) => vec![
+                ids(&rule.for_candidate_status_ids),
+                ("Minutes", fmt_optional(rule.minutes)),
+                ("Enforce", fmt_optional_bool(rule.enforce)),
+            ],
+            Rule::LastMinuteActionPreventionForCanceling(rule) => vec![
+                ids(&rule.for_candidate_status_ids),
+                ("Minutes", fmt_optional(rule.minutes)),
+                ("Enforce", fmt_optional_bool(rule.enforce)),
+            ],
+        }
+    }
+}
+
+impl ValidationRuleSettings {
+    /// `<ValidationRule>` attributes as written in the XML, unset values are empty strings.
+    pub fn attributes(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Type", format!("{:?}", self.rule_type)),
+            ("PositionTypeIDs", fmt_csv(&self.position_type_ids)),
+            ("FromMatchStatusId", fmt_optional(self.from_match_status_id)),
+            (
+                "FallbackShiftStatusId",
+                fmt_optional(self.fallback_shift_status_id),
+            ),
+            ("Enabled", fmt_bool(self.enabled)),
+            (
+                "EffectiveFrom",
+                fmt_optional_datetime(self.schedule.effective_from),
+            ),
+            (
+                "EffectiveUntil",
+                fmt_optional_datetime(self.schedule.effective_until),
+            ),
+            (
+                "ActiveWindows",
+                TimeWindow::fmt_list(&self.schedule.active_windows),
+            ),
+            (
+                "InactiveWindows",
+                TimeWindow::fmt_list(&self.schedule.inactive_windows),
+            ),
+        ]
+    }
+
+    /// Enabled and inside its `Schedule` at `at`.
+    pub fn is_active_at(&self, at: NaiveDateTime) -> bool {
+        self.enabled && self.schedule.is_active_at(at)
+    }
+
+    /// Sets a `<ValidationRule>` attribute from its XML text, the counterpart of `attributes()`. </br>
+    /// Parameters copied into the `<Rule>` entries are updated there as well.
+    pub fn set_attribute(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "Enabled" => self.enabled = bool_value(name, value)?.unwrap_or(false),
+            "PositionTypeIDs" => self.position_type_ids = ids_value(name, value)?,
+            "FromMatchStatusId" => self.from_match_status_id = i32_value(name, value)?,
+            "FallbackShiftStatusId" => self.fallback_shift_status_id = i32_value(name, value)?,
+            "EffectiveFrom" => self.schedule.effective_from = datetime_value(name, value)?,
+            "EffectiveUntil" => self.schedule.effective_until = datetime_value(name, value)?,
+            "ActiveWindows" => self.schedule.active_windows = windows_value(value)?,
+            "InactiveWindows" => self.schedule.inactive_windows = windows_value(value)?,
+            _ => {
+                return Err(format!(
+                    "Unknown attribute '{name}' for {:?} <ValidationRule>",
+                    self.rule_type
+                ))
+            }
+        }
+
+        for rule in &mut self.rules.rules {
+            match rule {
+                Rule::SideJobPrevention(rule) => {
+                    rule.position_type_ids = self.position_type_ids.clone();
+                    rule.from_match_status_id = self.from_match_status_id;
+                }
+                Rule::ExhaustionPrevention(rule) => {
+                    rule.fallback_shift_status_id = self.fallback_shift_status_id;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Parses an XML config, keeping its `root_attributes`.
+    pub fn from_xml(xml: &str) -> Result<Config, DeError> {
+        let mut config: Config = quick_xml::de::from_str(xml)?;
+        config.root_attributes = root_attributes(xml);
+        Ok(config)
+    }
+
+    /// Writes the config in the same layout the parser reads (comments of the source file are not kept).
+    pub fn to_xml(&self) -> Result<String, SeError> {
+        let mut xml = String::new();
+        let mut serializer = XmlSerializer::with_root(&mut xml, Some("config"))?;
+        serializer.indent(' ', 3);
+        self.serialize(serializer)?;
+
+        let attributes: String = self
+            .root_attributes
+            .iter()
+            .map(|(name, value)| format!(" {name}=\"{}\"", escape(value)))
+            .collect();
+        xml.insert_str("<config".len(), &attributes);
+        Ok(xml)
+    }
+}
+
+/// Attributes of the first element of `xml`, empty when it can't be read.
+fn root_attributes(xml: &str) -> Vec<(String, String)> {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(root) | Event::Empty(root)) => {
+                return root
+                    .attributes()
+                    .flatten()
+                    .filter_map(|attribute| {
+                        let value = attribute.unescape_value().ok()?;
+                        let name = String::from_utf8_lossy(attribute.key.as_ref());
+                        Some((name.to_string(), value.to_string()))
+                    })
+                    .collect()
+            }
+            Ok(Event::Eof) | Err(_) => return Vec::new(),
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Optional `<ValidationRule>` attributes are left out when unset.
+impl Serialize for ValidationRuleSettings {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (name, value) in self.attributes() {
+            if !value.is_empty() || name == "Enabled" {
+                map.serialize_entry(&format!("@{name}"), &value)?;
+            }
+        }
+        map.serialize_entry("Rules", &self.rules)?;
+        map.end()
+    }
+}
+
+/// Every attribute of the rule type is written, unset values as `""`.
+impl Serialize for Rule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (name, value) in self.attributes() {
+            map.serialize_entry(&format!("@{name}"), &value)?;
+        }
+        map.end()
+    }
+}
+
+/// Mirrors the XML layout, `<Rule>` attributes can only be typed once the parent `@Type` is known.
+#[derive(Deserialize)]
+struct RawValidationRuleSettings {
+    #[serde(rename = "@Type", alias = "Type")]
+    rule_type: RuleType,
+    #[serde(
+        rename = "@Enabled",
+        alias = "Enabled",
+        deserialize_with = "parse_bool"
+    )]
+    enabled: bool,
+    #[serde(
+        rename = "@FallbackShiftStatusId",
+        alias = "FallbackShiftStatusId",
+        deserialize_with = "parse_optional_i32",
+        default
+    )]
+    fallback_shift_status_id: Option<i32>,
     #[serde(
         rename = "@PositionTypeIDs",
+        alias = "PositionTypeIDs",
         deserialize_with = "parse_csv_string",
         default
     )]
-    pub position_type_ids: Vec<i32>,
-    #[serde(rename = "@FromMatchStatusId", default)]
-    pub from_match_status_id: Option<i32>,
+    position_type_ids: Vec<i32>,
+    #[serde(
+        rename = "@FromMatchStatusId",
+        alias = "FromMatchStatusId",
+        deserialize_with = "parse_optional_i32",
+        default
+    )]
+    from_match_status_id: Option<i32>,
+    #[serde(
+        rename = "@EffectiveFrom",
+        alias = "EffectiveFrom",
+        deserialize_with = "parse_datetime",
+        default
+    )]
+    effective_from: Option<NaiveDateTime>,
+    #[serde(
+        rename = "@EffectiveUntil",
+        alias = "EffectiveUntil",
+        deserialize_with = "parse_datetime",
+        default
+    )]
+    effective_until: Option<NaiveDateTime>,
+    #[serde(
+        rename = "@ActiveWindows",
+        alias = "ActiveWindows",
+        deserialize_with = "parse_windows",
+        default
+    )]
+    active_windows: Vec<TimeWindow>,
+    #[serde(
+        rename = "@InactiveWindows",
+        alias = "InactiveWindows",
+        deserialize_with = "parse_windows",
+        default
+    )]
+    inactive_windows: Vec<TimeWindow>,
     #[serde(rename = "Rules")]
-    pub rules: RulesContainer,
+    rules: RawRulesContainer,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
-pub struct RulesContainer {
+#[derive(Deserialize)]
+struct RawRulesContainer {
     #[serde(rename = "Rule", default)]
-    rules: Vec<Rule>,
+    rules: Vec<RawRule>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
-pub struct Rule {
+#[derive(Deserialize)]
+struct RawRule {
     #[serde(
         rename = "@ForCandidateStatusIds",
+        alias = "ForCandidateStatusIds",
         deserialize_with = "parse_csv_string",
         default
     )]
     for_candidate_status_ids: Vec<i32>,
-    #[serde(rename = "@Enforce", deserialize_with = "parse_bool", default)]
-    enforce: bool,
+    #[serde(
+        rename = "@Enforce",
+        alias = "Enforce",
+        deserialize_with = "parse_optional_bool",
+        default
+    )]
+    enforce: Option<bool>,
+    #[serde(
+        rename = "@ForShiftStatusIds",
+        alias = "ForShiftStatusIds",
+        deserialize_with = "parse_csv_string",
+        default
+    )]
+    for_shift_status_ids: Vec<i32>,
+    #[serde(
+        rename = "@HoursAllowed",
+        alias = "HoursAllowed",
+        deserialize_with = "parse_optional_i32",
+        default
+    )]
+    hours_allowed: Option<i32>,
+    #[serde(
+        rename = "@WithinXHours",
+        alias = "WithinXHours",
+        deserialize_with = "parse_optional_i32",
+        default
+    )]
+    within_x_hours: Option<i32>,
+    #[serde(
+        rename = "@Minutes",
+        alias = "Minutes",
+        deserialize_with = "parse_optional_i32",
+        default
+    )]
+    minutes: Option<i32>,
+    #[serde(
+        rename = "@IfShiftEndReasonIds",
+        alias = "IfShiftEndReasonIds",
+        deserialize_with = "parse_csv_string",
+        default
+    )]
+    if_shift_end_reason_ids: Vec<i32>,
+    #[serde(
+        rename = "@ForTheNextXDays",
+        alias = "ForTheNextXDays",
+        deserialize_with = "parse_optional_i32",
+        default
+    )]
+    for_the_next_x_days: Option<i32>,
+}
+
+impl From<RawValidationRuleSettings> for ValidationRuleSettings {
+    fn from(mut raw: RawValidationRuleSettings) -> Self {
+        let rules = std::mem::take(&mut raw.rules.rules)
+            .into_iter()
+            .map(|rule| raw.typed_rule(rule))
+            .collect();
+
+        Self {
+            rules: RulesContainer { rules },
+            rule_type: raw.rule_type,
+            enabled: raw.enabled,
+            fallback_shift_status_id: raw.fallback_shift_status_id,
+            position_type_ids: raw.position_type_ids,
+            from_match_status_id: raw.from_match_status_id,
+            schedule: Schedule {
+                effective_from: raw.effective_from,
+                effective_until: raw.effective_until,
+                active_windows: raw.active_windows,
+                inactive_windows: raw.inactive_windows,
+            },
+        }
+    }
+}
+
+impl RawValidationRuleSettings {
+    fn typed_rule(&self, rule: RawRule) -> Rule {
+        match self.rule_type {
+            RuleType::SideJobPrevention => Rule::SideJobPrevention(SideJobPrevention {
+                for_candidate_status_ids: rule.for_candidate_status_ids,
+                position_type_ids: self.position_type_ids.clone(),
+                from_match_status_id: self.from_match_status_id,
+                enforce: rule.enforce,
+            }),
+            RuleType::IndecisivePrevention => Rule::IndecisivePrevention(IndecisivePrevention {
+                for_candidate_status_ids: rule.for_candidate_status_ids,
+                if_shift_end_reason_ids: rule.if_shift_end_reason_ids,
+                for_the_next_x_days: rule.for_the_next_x_days,
+                enforce: rule.enforce,
+            }),
+            RuleType::ExhaustionPrevention => Rule::ExhaustionPrevention(ExhaustionPrevention {
+                for_candidate_status_ids: rule.for_candidate_status_ids,
+                for_shift_status_ids: rule.for_shift_status_ids,
+                fallback_shift_status_id: self.fallback_shift_status_id,
+                hours_allowed: rule.hours_allowed,
+                within_x_hours: rule.within_x_hours,
+                enforce: rule.enforce,
+            }),
+            RuleType::LastMinuteActionPreventionForBooking => {
+                Rule::LastMinuteActionPreventionForBooking(LastMinuteActionPreventionForBooking {
+                    for_candidate_status_ids: rule.for_candidate_status_ids,
+                    minutes: rule.minutes,
+                    enforce: rule.enforce,
+                })
+            }
+            RuleType::LastMinuteActionPreventionForCanceling => {
+                Rule::LastMinuteActionPreventionForCanceling(
+                    LastMinuteActionPreventionForCanceling {
+                        for_candidate_status_ids: rule.for_candidate_status_ids,
+                        minutes: rule.minutes,
+                        enforce: rule.enforce,
+                    },
+                )
+            }
+        }
+    }
+}
+
+/// Attribute value as text. XML attributes always are text, TOML / JSON / YAML configs may also use
+/// native values (`Enabled = true`, `Minutes = 30`, `PositionTypeIDs = [1, 2]`).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    Text(String),
+    Bool(bool),
+    Int(i64),
+    Ids(Vec<i64>),
+}
+
+fn text<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Scalar::deserialize(deserializer)? {
+        Scalar::Text(s) => s,
+        Scalar::Bool(b) => fmt_bool(b),
+        Scalar::Int(i) => i.to_string(),
+        Scalar::Ids(ids) => ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    })
 }
 
 fn parse_csv_string<'de, D>(deserializer: D) -> Result<Vec<i32>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: String = Deserialize::deserialize(deserializer)?;
-    s.split(',')
-        .filter(|s| !s.is_empty())
-        .map(|s| s.parse::<i32>().map_err(Error::custom))
-        .collect()
+    let s = text(deserializer)?;
+    csv_ids(&s).map_err(Error::custom)
+}
+
+/// Empty attribute (`HoursAllowed=""`) means the value is not configured.
+fn parse_optional_i32<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = text(deserializer)?;
+    optional_i32(&s).map_err(Error::custom)
+}
+
+/// Empty attribute (`Enforce=""`) means the value is not configured.
+fn parse_optional_bool<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = text(deserializer)?;
+    optional_bool(&s).map_err(Error::custom)
 }
 
 fn parse_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: String = Deserialize::deserialize(deserializer)?;
-    match s.to_lowercase().as_str() {
-        "true" => Ok(true),
-        "false" => Ok(false),
-        "" => Ok(false),
-        " " => Ok(false),
-        _ => Err(D::Error::custom(format!(
-            "Invalid [THIS IS WHERE WE NEED TO PROPAGATE OUR CUSTOM ERR] >> boolean value: {}",
-            s
-        ))),
+    let s = text(deserializer)?;
+    optional_bool(&s)
+        .map(|value| value.unwrap_or(false))
+        .map_err(Error::custom)
+}
+
+/// Empty attribute (`EffectiveUntil=""`) means unbounded.
+fn parse_datetime<'de, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = text(deserializer)?;
+    parse_optional_datetime(&s).map_err(Error::custom)
+}
+
+fn parse_windows<'de, D>(deserializer: D) -> Result<Vec<TimeWindow>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = text(deserializer)?;
+    TimeWindow::parse_list(&s).map_err(Error::custom)
+}
+
+/// Value type of a config attribute, shared by `validator::validate` and the schema export.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeKind {
+    RuleType,
+    /// `True` / `False` in any casing, empty when unset
+    Bool,
+    /// Comma separated integer ids, may be empty
+    Ids,
+    /// Integer, empty when unset
+    Int,
+    /// `2025-11-01` or `2025-11-01T06:00`, empty when unset
+    DateTime,
+    /// `;` separated `TimeWindow`s, may be empty
+    Windows,
+    Text,
+}
+
+impl AttributeKind {
+    pub fn of(attribute: &str) -> Self {
+        match attribute {
+            "Type" => AttributeKind::RuleType,
+            "Enabled" | "Enforce" => AttributeKind::Bool,
+            "PositionTypeIDs"
+            | "ForCandidateStatusIds"
+            | "ForShiftStatusIds"
+            | "IfShiftEndReasonIds" => AttributeKind::Ids,
+            "FallbackShiftStatusId"
+            | "FromMatchStatusId"
+            | "HoursAllowed"
+            | "WithinXHours"
+            | "Minutes"
+            | "ForTheNextXDays" => AttributeKind::Int,
+            "EffectiveFrom" | "EffectiveUntil" => AttributeKind::DateTime,
+            "ActiveWindows" | "InactiveWindows" => AttributeKind::Windows,
+            _ => AttributeKind::Text,
+        }
+    }
+}
+
+// Attribute value parsers shared by the serde deserializers above and `validator::validate`
+
+pub(crate) fn csv_ids(s: &str) -> Result<Vec<i32>, ParseIntError> {
+    s.split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<i32>())
+        .collect()
+}
+
+pub(crate) fn optional_i32(s: &str) -> Result<Option<i32>, ParseIntError> {
+    match s.trim() {
+        "" => Ok(None),
+        s => s.parse::<i32>().map(Some),
+    }
+}
+
+pub(crate) fn optional_bool(s: &str) -> Result<Option<bool>, String> {
+    match s.trim().to_lowercase().as_str() {
+        "" => Ok(None),
+        "true" => Ok(Some(true)),
+        "false" => Ok(Some(false)),
+        _ => Err(format!("Invalid boolean value: {}", s)),
     }
 }
 
-#[derive(Debug)]
+fn ids_value(name: &str, value: &str) -> Result<Vec<i32>, String> {
+    csv_ids(value).map_err(|_| invalid_value(name, value, "comma separated integer ids"))
+}
+
+fn i32_value(name: &str, value: &str) -> Result<Option<i32>, String> {
+    optional_i32(value).map_err(|_| invalid_value(name, value, "an integer"))
+}
+
+fn bool_value(name: &str, value: &str) -> Result<Option<bool>, String> {
+    optional_bool(value).map_err(|_| invalid_value(name, value, "True or False"))
+}
+
+fn datetime_value(name: &str, value: &str) -> Result<Option<NaiveDateTime>, String> {
+    parse_optional_datetime(value)
+        .map_err(|_| invalid_value(name, value, "a date like 2025-11-01 or 2025-11-01T06:00"))
+}
+
+fn windows_value(value: &str) -> Result<Vec<TimeWindow>, String> {
+    TimeWindow::parse_list(value)
+}
+
+fn invalid_value(name: &str, value: &str, expected: &str) -> String {
+    format!("Invalid value \"{value}\" for '{name}', expected {expected}")
+}
+
+fn fmt_csv(ids: &[i32]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn fmt_bool(value: bool) -> String {
+    if value { "True" } else { "False" }.to_string()
+}
+
+fn fmt_optional_bool(value: Option<bool>) -> String {
+    value.map(fmt_bool).unwrap_or_default()
+}
+
+fn fmt_optional(value: Option<i32>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Location {
-    message: String,
-    line: usize,
-    column: usize,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
 }
 
 impl fmt::Display for Location {
@@ -304,6 +996,133 @@ mod tests {
             buf.clear();
         }
     }
+    #[test]
+    fn test_rule_for_candidate_status() {
+        let settings: super::ValidationRuleSettings = quick_xml::de::from_str(
+            r#"<ValidationRule Type="SideJobPrevention" Enabled="True">
+                  <Rules>
+                     <Rule ForCandidateStatusIds="" Enforce="False" />
+                     <Rule ForCandidateStatusIds="2,3" Enforce="True" />
+                  </Rules>
+               </ValidationRule>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            settings.rules.for_candidate_status(3).unwrap().enforce(),
+            true
+        );
+        assert_eq!(
+            settings.rules.for_candidate_status(5).unwrap().enforce(),
+            false
+        );
+    }
+
+    #[test]
+    fn test_typed_rule_parsing() {
+        let settings: super::ValidationRuleSettings = quick_xml::de::from_str(
+            r#"<ValidationRule Type="ExhaustionPrevention" FallbackShiftStatusId="1" Enabled="True">
+                  <Rules>
+                     <Rule ForCandidateStatusIds="4" ForShiftStatusIds="" HoursAllowed="12" WithinXHours="" Enforce="" />
+                  </Rules>
+               </ValidationRule>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            settings.rules.rules,
+            vec![super::Rule::ExhaustionPrevention(
+                crate::handlers::rule_definitions::ExhaustionPrevention {
+                    for_candidate_status_ids: vec![4],
+                    for_shift_status_ids: vec![],
+                    fallback_shift_status_id: Some(1),
+                    hours_allowed: Some(12),
+                    within_x_hours: None,
+                    enforce: None,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_xml_round_trip() {
+        let xml = std::fs::read_to_string("validator_config.xml").unwrap();
+        let config: super::Config = quick_xml::de::from_str(&xml).unwrap();
+
+        let written = config.to_xml().unwrap();
+
+        assert_eq!(crate::config::validator::validate(&written), vec![]);
+        assert_eq!(
+            quick_xml::de::from_str::<super::Config>(&written).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn test_schedule_attributes() {
+        let mut settings: super::ValidationRuleSettings = quick_xml::de::from_str(
+            r#"<ValidationRule Type="ExhaustionPrevention" Enabled="True" EffectiveFrom="2025-11-01" ActiveWindows="Mon-Fri 08:00-18:00">
+                  <Rules />
+               </ValidationRule>"#,
+        )
+        .unwrap();
+        settings
+            .set_attribute("InactiveWindows", "12-24..01-02")
+            .unwrap();
+        assert!(settings.set_attribute("EffectiveUntil", "soon").is_err());
+
+        let attributes: Vec<_> = settings.attributes().into_iter().skip(5).collect();
+        assert_eq!(
+            attributes,
+            vec![
+                ("EffectiveFrom", "2025-11-01".to_string()),
+                ("EffectiveUntil", "".to_string()),
+                ("ActiveWindows", "Mon-Fri 08:00-18:00".to_string()),
+                ("InactiveWindows", "12-24..01-02".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xml_writer_format() {
+        let settings: super::ValidationRuleSettings = quick_xml::de::from_str(
+            r#"<ValidationRule Type="SideJobPrevention" PositionTypeIDs="1,2" FromMatchStatusId="0" Enabled="true">
+                  <Rules>
+                     <Rule ForCandidateStatusIds="2,3" Enforce="" />
+                  </Rules>
+               </ValidationRule>"#,
+        )
+        .unwrap();
+        let config = super::Config {
+            validation_rules: super::ValidationRulesContainer {
+                groups: super::GroupsContainer {
+                    validation_rules_groups: vec![super::ValidationRulesGroupSettings {
+                        group: "Shift".to_string(),
+                        validation_rules: vec![settings],
+                    }],
+                },
+            },
+            root_attributes: Vec::new(),
+        };
+
+        assert_eq!(
+            config.to_xml().unwrap(),
+            r#"<config>
+   <ValidationRules>
+      <Groups>
+         <ValidationRulesGroup Group="Shift">
+            <ValidationRule Type="SideJobPrevention" PositionTypeIDs="1,2" FromMatchStatusId="0" Enabled="True">
+               <Rules>
+                  <Rule ForCandidateStatusIds="2,3" Enforce=""/>
+               </Rules>
+            </ValidationRule>
+         </ValidationRulesGroup>
+      </Groups>
+   </ValidationRules>
+</config>"#
+        );
+    }
+
     fn is_num(value: &quick_xml::events::attributes::Attribute<'_>) -> bool {
         if let Ok(value) = value.unescape_value() {
             return value.trim().parse::<i32>().is_ok();
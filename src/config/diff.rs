@@ -0,0 +1,264 @@
+use super::config::{Config, ValidationRuleSettings, ValidationRulesGroupSettings};
+use crate::library::RuleType;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// What a hot reload changed between two `Config`s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigDiff {
+    pub changes: Vec<ConfigChange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    GroupAdded {
+        group: String,
+    },
+    GroupRemoved {
+        group: String,
+    },
+    RuleAdded {
+        group: String,
+        rule_type: RuleType,
+    },
+    RuleRemoved {
+        group: String,
+        rule_type: RuleType,
+    },
+    /// `attribute` is either a `<ValidationRule>` attribute (`Enabled`) or a `<Rule>` entry attribute (`Rule[0].Enforce`).
+    /// `None` when the attribute (or the whole `<Rule>` entry) does not exist on that side.
+    AttributeChanged {
+        group: String,
+        rule_type: RuleType,
+        attribute: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+impl ConfigDiff {
+    pub fn between(old: &Config, new: &Config) -> Self {
+        let old_groups = &old.validation_rules.groups.validation_rules_groups;
+        let new_groups = &new.validation_rules.groups.validation_rules_groups;
+        let mut changes = Vec::new();
+
+        for old_group in old_groups {
+            match new_groups.iter().find(|g| g.group == old_group.group) {
+                Some(new_group) => diff_group(old_group, new_group, &mut changes),
+                None => changes.push(ConfigChange::GroupRemoved {
+                    group: old_group.group.clone(),
+                }),
+            }
+        }
+        for new_group in new_groups {
+            if !old_groups.iter().any(|g| g.group == new_group.group) {
+                changes.push(ConfigChange::GroupAdded {
+                    group: new_group.group.clone(),
+                });
+            }
+        }
+
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Rules are matched by `Type` (and occurrence, in case the same type is listed twice).
+fn diff_group(
+    old: &ValidationRulesGroupSettings,
+    new: &ValidationRulesGroupSettings,
+    changes: &mut Vec<ConfigChange>,
+) {
+    let old_rules = keyed_rules(old);
+    let new_rules = keyed_rules(new);
+
+    for (key, old_rule) in &old_rules {
+        match new_rules.get(key) {
+            Some(new_rule) => {
+                let old_attributes = flatten(old_rule);
+                let new_attributes = flatten(new_rule);
+                let mut names: Vec<&String> = old_attributes.keys().collect();
+                names.extend(
+                    new_attributes
+                        .keys()
+                        .filter(|k| !old_attributes.contains_key(*k)),
+                );
+
+                for name in names {
+                    let (old_value, new_value) =
+                        (old_attributes.get(name), new_attributes.get(name));
+                    if old_value != new_value {
+                        changes.push(ConfigChange::AttributeChanged {
+                            group: new.group.clone(),
+                            rule_type: new_rule.rule_type.clone(),
+                            attribute: name.clone(),
+                            old: old_value.cloned(),
+                            new: new_value.cloned(),
+                        });
+                    }
+                }
+            }
+            None => changes.push(ConfigChange::RuleRemoved {
+                group: old.group.clone(),
+                rule_type: old_rule.rule_type.clone(),
+            }),
+        }
+    }
+    for (key, new_rule) in &new_rules {
+        if !old_rules.contains_key(key) {
+            changes.push(ConfigChange::RuleAdded {
+                group: new.group.clone(),
+                rule_type: new_rule.rule_type.clone(),
+            });
+        }
+    }
+}
+
+fn keyed_rules(
+    group: &ValidationRulesGroupSettings,
+) -> BTreeMap<(String, usize), &ValidationRuleSettings> {
+    let mut keyed = BTreeMap::new();
+    for rule in &group.validation_rules {
+        let name = format!("{:?}", rule.rule_type);
+        let occurrence = keyed.keys().filter(|(n, _)| *n == name).count();
+        keyed.insert((name, occurrence), rule);
+    }
+    keyed
+}
+
+/// `<ValidationRule>` attributes followed by `Rule[i].Attribute` for every `<Rule>` entry.
+fn flatten(settings: &ValidationRuleSettings) -> BTreeMap<String, String> {
+    let mut attributes: BTreeMap<String, String> = settings
+        .attributes()
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+    for (i, rule) in settings.rules.rules.iter().enumerate() {
+        for (name, value) in rule.attributes() {
+            attributes.insert(format!("Rule[{i}].{name}"), value);
+        }
+    }
+    attributes
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = |v: &Option<String>| match v {
+            Some(v) => format!("{v:?}"),
+            None => "(unset)".to_string(),
+        };
+        match self {
+            ConfigChange::GroupAdded { group } => write!(f, "+ group {group}"),
+            ConfigChange::GroupRemoved { group } => write!(f, "- group {group}"),
+            ConfigChange::RuleAdded { group, rule_type } => write!(f, "+ {group}/{rule_type:?}"),
+            ConfigChange::RuleRemoved { group, rule_type } => write!(f, "- {group}/{rule_type:?}"),
+            ConfigChange::AttributeChanged {
+                group,
+                rule_type,
+                attribute,
+                old,
+                new,
+            } => write!(
+                f,
+                "~ {group}/{rule_type:?} {attribute}: {} -> {}",
+                value(old),
+                value(new)
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "no changes");
+        }
+        let lines: Vec<String> = self.changes.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::from_str;
+
+    fn config(xml_groups: &str) -> Config {
+        from_str(&format!(
+            "<config><ValidationRules><Groups>{xml_groups}</Groups></ValidationRules></config>"
+        ))
+        .unwrap()
+    }
+
+    const SHIFT_GROUP: &str = r#"
+        <ValidationRulesGroup Group="Shift">
+            <ValidationRule Type="SideJobPrevention" PositionTypeIDs="2" FromMatchStatusId="0" Enabled="True">
+                <Rules>
+                    <Rule ForCandidateStatusIds="" Enforce="False" />
+                </Rules>
+            </ValidationRule>
+        </ValidationRulesGroup>"#;
+
+    #[test]
+    fn test_identical_configs() {
+        let diff = ConfigDiff::between(&config(SHIFT_GROUP), &config(SHIFT_GROUP));
+        assert_eq!(diff.is_empty(), true);
+        assert_eq!(diff.to_string(), "no changes");
+    }
+
+    #[test]
+    fn test_attribute_changes() {
+        let new = SHIFT_GROUP
+            .replace(r#"Enforce="False""#, r#"Enforce="True""#)
+            .replace(r#"PositionTypeIDs="2""#, r#"PositionTypeIDs="2,3""#);
+
+        let diff = ConfigDiff::between(&config(SHIFT_GROUP), &config(&new));
+
+        assert_eq!(
+            diff.to_string(),
+            "~ Shift/SideJobPrevention PositionTypeIDs: \"2\" -> \"2,3\"\n\
+             ~ Shift/SideJobPrevention Rule[0].Enforce: \"False\" -> \"True\""
+        );
+    }
+
+    #[test]
+    fn test_added_and_removed() {
+        let new = SHIFT_GROUP
+            .replace(
+                "</ValidationRulesGroup>",
+                r#"<ValidationRule Type="LastMinuteActionPreventionForBooking" Enabled="True">
+                      <Rules><Rule ForCandidateStatusIds="" Minutes="60" Enforce="True" /></Rules>
+                   </ValidationRule>
+                   </ValidationRulesGroup>"#,
+            )
+            .replace(
+                r#"<Rule ForCandidateStatusIds="" Enforce="False" />"#,
+                r#"<Rule ForCandidateStatusIds="" Enforce="False" />
+                   <Rule ForCandidateStatusIds="3" Enforce="True" />"#,
+            );
+        let old = format!(
+            r#"{SHIFT_GROUP}
+            <ValidationRulesGroup Group="Candidate">
+                <ValidationRule Type="IndecisivePrevention" Enabled="True">
+                    <Rules><Rule ForCandidateStatusIds="" Enforce="False" /></Rules>
+                </ValidationRule>
+            </ValidationRulesGroup>"#
+        );
+
+        let diff = ConfigDiff::between(&config(&old), &config(&new));
+
+        assert_eq!(
+            diff.to_string(),
+            "~ Shift/SideJobPrevention Rule[1].Enforce: (unset) -> \"True\"\n\
+             ~ Shift/SideJobPrevention Rule[1].ForCandidateStatusIds: (unset) -> \"3\"\n\
+             + Shift/LastMinuteActionPreventionForBooking\n\
+             - group Candidate"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use super::config::Config;
+use super::validator;
+use crate::library::{Error, Result};
+
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+/// File format of a rule config, chosen by file extension. </br>
+/// TOML / JSON / YAML configs mirror the XML layout, attributes are plain keys (`Type` instead of `@Type`):
+/// ```toml
+/// [[ValidationRules.Groups.ValidationRulesGroup]]
+/// Group = "Shift"
+///
+/// [[ValidationRules.Groups.ValidationRulesGroup.ValidationRule]]
+/// Type = "SideJobPrevention"
+/// PositionTypeIDs = "1,2"
+/// Enabled = "True"
+///
+/// [[ValidationRules.Groups.ValidationRulesGroup.ValidationRule.Rules.Rule]]
+/// ForCandidateStatusIds = ""
+/// Enforce = "False"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Xml,
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub const EXTENSIONS: &[(&str, ConfigFormat)] = &[
+        ("xml", ConfigFormat::Xml),
+        ("toml", ConfigFormat::Toml),
+        ("json", ConfigFormat::Json),
+        ("yaml", ConfigFormat::Yaml),
+        ("yml", ConfigFormat::Yaml),
+    ];
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        Self::EXTENSIONS
+            .iter()
+            .find(|(ext, _)| *ext == extension)
+            .map(|(_, format)| *format)
+            .ok_or_else(|| Error::UnsupportedFormat(path.to_path_buf()))
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ConfigFormat::Xml => "xml",
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Json => "json",
+            ConfigFormat::Yaml => "yaml",
+        }
+    }
+
+    /// XML is checked by `validator::validate` first, so every bad attribute is reported with its location.
+    pub fn parse(&self, text: &str) -> Result<Config> {
+        Ok(match self {
+            ConfigFormat::Xml => {
+                let problems = validator::validate(text);
+                if !problems.is_empty() {
+                    return Err(Error::InvalidConfig(problems));
+                }
+                Config::from_xml(text)?
+            }
+            ConfigFormat::Toml => toml::from_str(text)?,
+            ConfigFormat::Json => serde_json::from_str(text)?,
+            ConfigFormat::Yaml => serde_yaml::from_str(text)?,
+        })
+    }
+
+    pub fn write(&self, config: &Config) -> Result<String> {
+        let value = || serde_json::to_value(config).map(without_attribute_prefix);
+        Ok(match self {
+            ConfigFormat::Xml => config.to_xml()?,
+            ConfigFormat::Toml => toml::to_string(&value()?)?,
+            ConfigFormat::Json => serde_json::to_string_pretty(&value()?)?,
+            ConfigFormat::Yaml => serde_yaml::to_string(&value()?)?,
+        })
+    }
+}
+
+/// `@Type` -> `Type`, the `@` only tells quick-xml to write an attribute.
+fn without_attribute_prefix(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let key = key.strip_prefix('@').map(str::to_string).unwrap_or(key);
+                    (key, without_attribute_prefix(value))
+                })
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(values) => {
+            Value::Array(values.into_iter().map(without_attribute_prefix).collect())
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn shipped_config() -> Config {
+        ConfigFormat::Xml
+            .parse(&std::fs::read_to_string("validator_config.xml").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_formats_round_trip() {
+        let config = shipped_config();
+
+        for format in [
+            ConfigFormat::Xml,
+            ConfigFormat::Toml,
+            ConfigFormat::Json,
+            ConfigFormat::Yaml,
+        ] {
+            let written = format.write(&config).unwrap();
+            assert_eq!(format.parse(&written).unwrap(), config, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_native_values_are_accepted() {
+        let json = r#"{
+            "ValidationRules": { "Groups": { "ValidationRulesGroup": [{
+                "Group": "Shift",
+                "ValidationRule": [{
+                    "Type": "SideJobPrevention",
+                    "PositionTypeIDs": [1, 2],
+                    "FromMatchStatusId": 0,
+                    "Enabled": true,
+                    "Rules": { "Rule": [{ "ForCandidateStatusIds": "2,3", "Enforce": "false" }] }
+                }]
+            }]}}
+        }"#;
+        let xml = r#"<config><ValidationRules><Groups>
+            <ValidationRulesGroup Group="Shift">
+               <ValidationRule Type="SideJobPrevention" PositionTypeIDs="1,2" FromMatchStatusId="0" Enabled="True">
+                  <Rules><Rule ForCandidateStatusIds="2,3" Enforce="False" /></Rules>
+               </ValidationRule>
+            </ValidationRulesGroup>
+        </Groups></ValidationRules></config>"#;
+
+        assert_eq!(
+            ConfigFormat::Json.parse(json).unwrap(),
+            ConfigFormat::Xml.parse(xml).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_by_extension() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("rules/office.YML")).unwrap(),
+            ConfigFormat::Yaml
+        );
+        assert!(matches!(
+            ConfigFormat::from_path(Path::new("rules.ini")),
+            Err(Error::UnsupportedFormat(_))
+        ));
+    }
+}
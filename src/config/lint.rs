@@ -0,0 +1,269 @@
+use super::config::{Config, Rule, ValidationRuleSettings};
+use crate::library::RuleType;
+
+use std::fmt;
+
+/// Semantic problem of a config that parsed fine but would not behave as intended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigLint {
+    pub group: String,
+    pub rule_type: RuleType,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigLint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{:?}: {}", self.group, self.rule_type, self.message)
+    }
+}
+
+/// Flags missing required parameters (of enabled rules), contradictory values, duplicate rule types
+/// in a group, overlapping `ForCandidateStatusIds`, rules placed in the wrong group and schedules that never apply.
+pub fn lint(config: &Config) -> Vec<ConfigLint> {
+    let mut lints = Vec::new();
+
+    for group in &config.validation_rules.groups.validation_rules_groups {
+        let mut push = |rule_type: &RuleType, message: String| {
+            lints.push(ConfigLint {
+                group: group.group.clone(),
+                rule_type: rule_type.clone(),
+                message,
+            })
+        };
+
+        for (i, settings) in group.validation_rules.iter().enumerate() {
+            let rule_type = &settings.rule_type;
+
+            if group.validation_rules[..i]
+                .iter()
+                .any(|other| other.rule_type == *rule_type)
+            {
+                push(rule_type, "Duplicate rule type in group".to_string());
+            }
+            if rule_type.group() != group.group {
+                push(
+                    rule_type,
+                    format!("Rule belongs to Group=\"{}\"", rule_type.group()),
+                );
+            }
+            if let (Some(from), Some(until)) = (
+                settings.schedule.effective_from,
+                settings.schedule.effective_until,
+            ) {
+                if from >= until {
+                    push(
+                        rule_type,
+                        format!("EffectiveUntil ({until}) is not after EffectiveFrom ({from}), the rule never applies"),
+                    );
+                }
+            }
+            for message in overlapping_candidate_statuses(settings) {
+                push(rule_type, message);
+            }
+            for (n, rule) in settings.rules.rules.iter().enumerate() {
+                for message in check_parameters(rule, settings.enabled) {
+                    push(rule_type, format!("Rule[{n}] {message}"));
+                }
+            }
+        }
+    }
+
+    lints
+}
+
+fn overlapping_candidate_statuses(settings: &ValidationRuleSettings) -> Vec<String> {
+    let rules = &settings.rules.rules;
+    let mut messages = Vec::new();
+
+    for (i, rule) in rules.iter().enumerate() {
+        for (j, other) in rules.iter().enumerate().skip(i + 1) {
+            let (ids, other_ids) = (
+                rule.for_candidate_status_ids(),
+                other.for_candidate_status_ids(),
+            );
+            if ids.is_empty() && other_ids.is_empty() {
+                messages.push(format!(
+                    "Rule[{i}] and Rule[{j}] both apply to every candidate status"
+                ));
+                continue;
+            }
+            let shared: Vec<String> = ids
+                .iter()
+                .filter(|id| other_ids.contains(id))
+                .map(|id| id.to_string())
+                .collect();
+            if !shared.is_empty() {
+                messages.push(format!(
+                    "Rule[{i}] and Rule[{j}] overlap on ForCandidateStatusIds {}",
+                    shared.join(",")
+                ));
+            }
+        }
+    }
+
+    messages
+}
+
+fn check_parameters(rule: &Rule, enabled: bool) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut required = |name: &str, missing: bool| {
+        if enabled && missing {
+            messages.push(format!("is missing required '{name}'"));
+        }
+    };
+
+    match rule {
+        Rule::SideJobPrevention(_) => {}
+        Rule::IndecisivePrevention(rule) => {
+            required(
+                "IfShiftEndReasonIds",
+                rule.if_shift_end_reason_ids.is_empty(),
+            );
+            required("ForTheNextXDays", rule.for_the_next_x_days.is_none());
+        }
+        Rule::ExhaustionPrevention(rule) => {
+            required("HoursAllowed", rule.hours_allowed.is_none());
+            required("WithinXHours", rule.within_x_hours.is_none());
+        }
+        Rule::LastMinuteActionPreventionForBooking(rule) => {
+            required("Minutes", rule.minutes.is_none());
+        }
+        Rule::LastMinuteActionPreventionForCanceling(rule) => {
+            required("Minutes", rule.minutes.is_none());
+        }
+    }
+
+    let positive = |name: &str, value: Option<i32>| {
+        value
+            .filter(|v| *v <= 0)
+            .map(|v| format!("'{name}' must be positive, got {v}"))
+    };
+    match rule {
+        Rule::SideJobPrevention(_) => {}
+        Rule::IndecisivePrevention(rule) => {
+            messages.extend(positive("ForTheNextXDays", rule.for_the_next_x_days));
+        }
+        Rule::ExhaustionPrevention(rule) => {
+            messages.extend(positive("HoursAllowed", rule.hours_allowed));
+            messages.extend(positive("WithinXHours", rule.within_x_hours));
+            if let (Some(allowed), Some(within)) = (rule.hours_allowed, rule.within_x_hours) {
+                // At most `within` hours fit into the window, the rule only triggers above `allowed`
+                if allowed >= within {
+                    messages.push(format!(
+                        "HoursAllowed ({allowed}) is not less than WithinXHours ({within}), the rule can never trigger"
+                    ));
+                }
+            }
+        }
+        Rule::LastMinuteActionPreventionForBooking(rule) => {
+            messages.extend(positive("Minutes", rule.minutes));
+        }
+        Rule::LastMinuteActionPreventionForCanceling(rule) => {
+            messages.extend(positive("Minutes", rule.minutes));
+        }
+    }
+
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::from_str;
+
+    fn lints(xml_groups: &str) -> Vec<String> {
+        let config: Config = from_str(&format!(
+            "<config><ValidationRules><Groups>{xml_groups}</Groups></ValidationRules></config>"
+        ))
+        .unwrap();
+        lint(&config).iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_missing_and_contradictory_parameters() {
+        let contradictory = lints(
+            r#"<ValidationRulesGroup Group="Shift">
+                  <ValidationRule Type="LastMinuteActionPreventionForBooking" Enabled="True">
+                     <Rules><Rule ForCandidateStatusIds="" Minutes="" Enforce="True" /></Rules>
+                  </ValidationRule>
+                  <ValidationRule Type="LastMinuteActionPreventionForCanceling" Enabled="False">
+                     <Rules><Rule ForCandidateStatusIds="" Minutes="" Enforce="True" /></Rules>
+                  </ValidationRule>
+                  <ValidationRule Type="ExhaustionPrevention" Enabled="True">
+                     <Rules><Rule ForCandidateStatusIds="" HoursAllowed="30" WithinXHours="24" Enforce="True" /></Rules>
+                  </ValidationRule>
+               </ValidationRulesGroup>"#,
+        );
+
+        assert_eq!(
+            contradictory,
+            vec![
+                "Shift/LastMinuteActionPreventionForBooking: Rule[0] is missing required 'Minutes'",
+                "Shift/ExhaustionPrevention: Rule[0] HoursAllowed (30) is not less than WithinXHours (24), the rule can never trigger",
+            ]
+        );
+
+        let equal = lints(
+            r#"<ValidationRulesGroup Group="Shift">
+                  <ValidationRule Type="ExhaustionPrevention" Enabled="True">
+                     <Rules><Rule ForCandidateStatusIds="" HoursAllowed="24" WithinXHours="24" Enforce="True" /></Rules>
+                  </ValidationRule>
+               </ValidationRulesGroup>"#,
+        );
+        assert_eq!(
+            equal,
+            vec!["Shift/ExhaustionPrevention: Rule[0] HoursAllowed (24) is not less than WithinXHours (24), the rule can never trigger"]
+        );
+    }
+
+    #[test]
+    fn test_structural_problems() {
+        let lints = lints(
+            r#"<ValidationRulesGroup Group="Shift">
+                  <ValidationRule Type="SideJobPrevention" Enabled="True">
+                     <Rules>
+                        <Rule ForCandidateStatusIds="1,2" Enforce="True" />
+                        <Rule ForCandidateStatusIds="2,3" Enforce="False" />
+                     </Rules>
+                  </ValidationRule>
+                  <ValidationRule Type="SideJobPrevention" Enabled="False">
+                     <Rules><Rule ForCandidateStatusIds="" /><Rule ForCandidateStatusIds="" /></Rules>
+                  </ValidationRule>
+                  <ValidationRule Type="IndecisivePrevention" Enabled="False">
+                     <Rules><Rule ForCandidateStatusIds="" IfShiftEndReasonIds="1" ForTheNextXDays="2" /></Rules>
+                  </ValidationRule>
+                  <ValidationRule Type="ExhaustionPrevention" Enabled="False" EffectiveFrom="2025-11-01" EffectiveUntil="2025-10-01">
+                     <Rules />
+                  </ValidationRule>
+               </ValidationRulesGroup>"#,
+        );
+
+        assert_eq!(
+            lints,
+            vec![
+                "Shift/SideJobPrevention: Rule[0] and Rule[1] overlap on ForCandidateStatusIds 2",
+                "Shift/SideJobPrevention: Duplicate rule type in group",
+                "Shift/SideJobPrevention: Rule[0] and Rule[1] both apply to every candidate status",
+                "Shift/IndecisivePrevention: Rule belongs to Group=\"Candidate\"",
+                "Shift/ExhaustionPrevention: EffectiveUntil (2025-10-01 00:00:00) is not after EffectiveFrom (2025-11-01 00:00:00), the rule never applies",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shipped_config() {
+        let config: Config =
+            from_str(&std::fs::read_to_string("validator_config.xml").unwrap()).unwrap();
+        let lints: Vec<String> = lint(&config).iter().map(|l| l.to_string()).collect();
+
+        assert_eq!(
+            lints,
+            vec![
+                "Candidate/IndecisivePrevention: Rule[0] is missing required 'IfShiftEndReasonIds'",
+                "Candidate/IndecisivePrevention: Rule[0] is missing required 'ForTheNextXDays'",
+                "Shift/LastMinuteActionPreventionForBooking: Rule[0] is missing required 'Minutes'",
+            ]
+        );
+    }
+}
@@ -1,2 +1,10 @@
+#[allow(clippy::module_inception)]
 pub mod config;
-pub mod prelude;
\ No newline at end of file
+pub mod diff;
+pub mod format;
+pub mod lint;
+pub mod overlay;
+pub mod prelude;
+pub mod schedule;
+pub mod schema;
+pub mod validator;
@@ -0,0 +1,364 @@
+use super::config::{csv_ids, Config, Location};
+use super::validator::{self, location};
+use crate::library::{Error, Result, RuleType};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// Partial config layered over the base file. </br>
+/// Same layout as the base config, but only with the attributes to override: `<ValidationRule>`
+/// entries are addressed by their `Group` and `Type`, `<Rule>` entries by their `ForCandidateStatusIds`.
+/// ```xml
+/// <ValidationRulesGroup Group="Shift">
+///    <ValidationRule Type="ExhaustionPrevention" Enabled="False">
+///       <Rules>
+///          <Rule ForCandidateStatusIds="1" HoursAllowed="10" />
+///       </Rules>
+///    </ValidationRule>
+/// </ValidationRulesGroup>
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    pub source: PathBuf,
+    overrides: Vec<Override>,
+}
+
+/// Single attribute set by an overlay, `rule` is `None` for `<ValidationRule>` attributes.
+#[derive(Debug, Clone, PartialEq)]
+struct Override {
+    group: String,
+    rule_type: RuleType,
+    rule: Option<Vec<i32>>,
+    attribute: String,
+    value: String,
+    line: usize,
+    column: usize,
+}
+
+impl Overlay {
+    pub fn parse(source: PathBuf, xml: &str) -> Result<Self> {
+        let problems = validator::validate_overlay(xml);
+        if !problems.is_empty() {
+            return Err(Error::InvalidOverlay(source, problems));
+        }
+
+        let mut reader = Reader::from_str(xml);
+        let mut overrides = Vec::new();
+        let mut group = String::new();
+        let mut rule_type = None;
+
+        loop {
+            let tag_start = reader.buffer_position() as usize;
+            match reader.read_event()? {
+                Event::Start(ref e) | Event::Empty(ref e) => {
+                    let at = location(xml, tag_start, String::new());
+                    let attributes = attributes(e)?;
+                    let value_of = |key: &str| {
+                        attributes
+                            .iter()
+                            .find(|(k, _)| k == key)
+                            .map(|(_, v)| v.clone())
+                            .unwrap_or_default()
+                    };
+                    // Values were already checked by validate_overlay
+                    let rule = match e.name().as_ref() {
+                        b"ValidationRulesGroup" => {
+                            group = value_of("Group");
+                            continue;
+                        }
+                        b"ValidationRule" => {
+                            rule_type = RuleType::from_str(&value_of("Type")).ok();
+                            None
+                        }
+                        b"Rule" => {
+                            Some(csv_ids(&value_of("ForCandidateStatusIds")).unwrap_or_default())
+                        }
+                        _ => continue,
+                    };
+                    let Some(rule_type) = &rule_type else {
+                        continue;
+                    };
+
+                    let skip = if rule.is_some() {
+                        "ForCandidateStatusIds"
+                    } else {
+                        "Type"
+                    };
+                    for (attribute, value) in attributes.into_iter().filter(|(k, _)| k != skip) {
+                        overrides.push(Override {
+                            group: group.clone(),
+                            rule_type: rule_type.clone(),
+                            rule: rule.clone(),
+                            attribute,
+                            value,
+                            line: at.line,
+                            column: at.column,
+                        });
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(Self { source, overrides })
+    }
+}
+
+fn attributes(tag: &BytesStart) -> Result<Vec<(String, String)>> {
+    let mut attributes = Vec::new();
+    for attr in tag.attributes() {
+        let attr = attr?;
+        attributes.push((
+            String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+            attr.unescape_value()?.to_string(),
+        ));
+    }
+    Ok(attributes)
+}
+
+/// Which file every effective value of a layered config comes from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Provenance {
+    base: PathBuf,
+    /// (group, rule type, attribute) -> overlay that set the value last
+    overridden: BTreeMap<(String, String, String), PathBuf>,
+}
+
+impl Provenance {
+    pub fn new(base: PathBuf) -> Self {
+        Self {
+            base,
+            overridden: BTreeMap::new(),
+        }
+    }
+
+    /// `attribute` is named like in `ConfigDiff`, e.g. `Enabled` or `Rule[0].Enforce`.
+    pub fn source_of(&self, group: &str, rule_type: &RuleType, attribute: &str) -> &Path {
+        self.overridden
+            .get(&(
+                group.to_string(),
+                format!("{rule_type:?}"),
+                attribute.to_string(),
+            ))
+            .unwrap_or(&self.base)
+    }
+
+    pub fn is_overridden(&self) -> bool {
+        !self.overridden.is_empty()
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.overridden.is_empty() {
+            return write!(f, "all values from {}", self.base.display());
+        }
+        let lines: Vec<String> = self
+            .overridden
+            .iter()
+            .map(|((group, rule_type, attribute), source)| {
+                format!("{group}/{rule_type} {attribute} <- {}", source.display())
+            })
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Applies the overlays in order on top of `base`, later overlays win. </br>
+/// Overlays can only override what the base config defines, anything else is reported with its location.
+pub fn merge(
+    base: &Config,
+    base_source: &Path,
+    overlays: &[Overlay],
+) -> Result<(Config, Provenance)> {
+    let mut config = base.clone();
+    let mut provenance = Provenance::new(base_source.to_path_buf());
+
+    for overlay in overlays {
+        let mut problems = Vec::new();
+        for o in &overlay.overrides {
+            match apply(&mut config, o) {
+                Ok(attribute) => {
+                    provenance.overridden.insert(
+                        (o.group.clone(), format!("{:?}", o.rule_type), attribute),
+                        overlay.source.clone(),
+                    );
+                }
+                Err(message) => problems.push(Location {
+                    message,
+                    line: o.line,
+                    column: o.column,
+                }),
+            }
+        }
+        if !problems.is_empty() {
+            return Err(Error::InvalidOverlay(overlay.source.clone(), problems));
+        }
+    }
+
+    Ok((config, provenance))
+}
+
+/// Returns the attribute name as used by `Provenance`.
+fn apply(config: &mut Config, o: &Override) -> std::result::Result<String, String> {
+    let group = config
+        .validation_rules
+        .groups
+        .validation_rules_groups
+        .iter_mut()
+        .find(|g| g.group == o.group)
+        .ok_or_else(|| format!("Group '{}' is not defined in the base config", o.group))?;
+    let settings = group
+        .validation_rules
+        .iter_mut()
+        .find(|r| r.rule_type == o.rule_type)
+        .ok_or_else(|| {
+            format!(
+                "{}/{:?} is not defined in the base config",
+                o.group, o.rule_type
+            )
+        })?;
+
+    let Some(ids) = &o.rule else {
+        settings.set_attribute(&o.attribute, &o.value)?;
+        return Ok(o.attribute.clone());
+    };
+
+    let same_ids = |rule_ids: &[i32]| {
+        let (mut a, mut b) = (rule_ids.to_vec(), ids.clone());
+        a.sort();
+        b.sort();
+        a == b
+    };
+    let (i, rule) = settings
+        .rules
+        .rules
+        .iter_mut()
+        .enumerate()
+        .find(|(_, rule)| same_ids(rule.for_candidate_status_ids()))
+        .ok_or_else(|| {
+            format!(
+                "{}/{:?} has no <Rule> with ForCandidateStatusIds=\"{}\" in the base config",
+                o.group,
+                o.rule_type,
+                ids.iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        })?;
+    rule.set_attribute(&o.attribute, &o.value)?;
+    Ok(format!("Rule[{i}].{}", o.attribute))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::from_str;
+
+    fn base() -> Config {
+        from_str(&std::fs::read_to_string("test_config.xml").unwrap()).unwrap()
+    }
+
+    fn overlay(name: &str, groups: &str) -> Overlay {
+        Overlay::parse(
+            PathBuf::from(name),
+            &format!(
+                "<config><ValidationRules><Groups>{groups}</Groups></ValidationRules></config>"
+            ),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_overlays_are_applied_in_order() {
+        let office = overlay(
+            "office.xml",
+            r#"<ValidationRulesGroup Group="Shift">
+                  <ValidationRule Type="SideJobPrevention" PositionTypeIDs="1,2">
+                     <Rules><Rule ForCandidateStatusIds="4,3,2" Enforce="False" /></Rules>
+                  </ValidationRule>
+               </ValidationRulesGroup>"#,
+        );
+        let tenant = overlay(
+            "tenant.xml",
+            r#"<ValidationRulesGroup Group="Shift">
+                  <ValidationRule Type="SideJobPrevention" Enabled="False" PositionTypeIDs="3" />
+               </ValidationRulesGroup>"#,
+        );
+
+        let (config, provenance) =
+            merge(&base(), Path::new("base.xml"), &[office, tenant]).unwrap();
+
+        let settings =
+            &config.validation_rules.groups.validation_rules_groups[0].validation_rules[0];
+        assert_eq!(settings.enabled, false);
+        assert_eq!(settings.position_type_ids, vec![3]);
+        assert_eq!(settings.rules.rules[1].enforce(), false);
+        assert_eq!(settings.rules.rules[0].enforce(), true);
+
+        let source_of = |attribute| {
+            provenance
+                .source_of("Shift", &RuleType::SideJobPrevention, attribute)
+                .to_path_buf()
+        };
+        assert_eq!(source_of("PositionTypeIDs"), PathBuf::from("tenant.xml"));
+        assert_eq!(source_of("Rule[1].Enforce"), PathBuf::from("office.xml"));
+        assert_eq!(source_of("Rule[0].Enforce"), PathBuf::from("base.xml"));
+
+        // The parent parameters copied into the typed rules follow the override
+        match &settings.rules.rules[0] {
+            crate::config::config::Rule::SideJobPrevention(rule) => {
+                assert_eq!(rule.position_type_ids, vec![3])
+            }
+            rule => panic!("unexpected rule {rule:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_targets_are_reported() {
+        let broken = overlay(
+            "broken.xml",
+            r#"<ValidationRulesGroup Group="Shift">
+<ValidationRule Type="ExhaustionPrevention" Enabled="False" />
+<ValidationRule Type="SideJobPrevention">
+   <Rules><Rule ForCandidateStatusIds="9" Enforce="False" /></Rules>
+</ValidationRule>
+</ValidationRulesGroup>"#,
+        );
+
+        let Err(Error::InvalidOverlay(source, problems)) =
+            merge(&base(), Path::new("base.xml"), &[broken])
+        else {
+            panic!("overlay was accepted");
+        };
+
+        assert_eq!(source, PathBuf::from("broken.xml"));
+        assert_eq!(
+            problems.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+            vec![
+                "Error at line 2, column 1: Shift/ExhaustionPrevention is not defined in the base config",
+                "Error at line 4, column 11: Shift/SideJobPrevention has no <Rule> with ForCandidateStatusIds=\"9\" in the base config",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overlay_values_are_validated() {
+        let result = Overlay::parse(
+            PathBuf::from("bad.xml"),
+            r#"<config><ValidationRules><Groups><ValidationRulesGroup Group="Shift">
+                  <ValidationRule Type="SideJobPrevention" Enabled="maybe" />
+               </ValidationRulesGroup></Groups></ValidationRules></config>"#,
+        );
+
+        assert!(matches!(result, Err(Error::InvalidOverlay(_, problems)) if problems.len() == 1));
+    }
+}
@@ -1 +1,5 @@
 pub use super::config::*;
+pub use super::diff::*;
+pub use super::format::ConfigFormat;
+pub use super::overlay::{Overlay, Provenance};
+pub use super::schedule::{Schedule, TimeWindow};
@@ -0,0 +1,294 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike, Weekday};
+use std::fmt;
+use std::str::FromStr;
+
+/// `<ValidationRule>` attributes that make up its `Schedule`.
+pub const SCHEDULE_ATTRIBUTES: &[&str] = &[
+    "EffectiveFrom",
+    "EffectiveUntil",
+    "ActiveWindows",
+    "InactiveWindows",
+];
+
+/// When an enabled rule applies, evaluated at `ValidationRequest::requested_at` (local time). </br>
+/// An empty schedule applies all the time:
+/// - `EffectiveFrom="2025-11-01"` / `EffectiveUntil="2026-01-01T06:00"` bound it (from inclusive, until exclusive)
+/// - `ActiveWindows="Mon-Fri 08:00-18:00; Sat"` restricts it to the recurring windows
+/// - `InactiveWindows="12-24..01-02"` switches it off in the recurring windows (wins over `ActiveWindows`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schedule {
+    pub effective_from: Option<NaiveDateTime>,
+    pub effective_until: Option<NaiveDateTime>,
+    pub active_windows: Vec<TimeWindow>,
+    pub inactive_windows: Vec<TimeWindow>,
+}
+
+impl Schedule {
+    pub fn is_active_at(&self, at: NaiveDateTime) -> bool {
+        self.effective_from.is_none_or(|from| from <= at)
+            && self.effective_until.is_none_or(|until| at < until)
+            && (self.active_windows.is_empty()
+                || self.active_windows.iter().any(|w| w.contains(at)))
+            && !self.inactive_windows.iter().any(|w| w.contains(at))
+    }
+}
+
+/// Recurring window of a `Schedule`, written as:
+/// - `Mon-Fri 08:00-18:00`, `Sat,Sun`, `22:00-06:00` - week days and / or hours, a window that ends
+///   before it starts runs past midnight and belongs to the day it starts on
+/// - `12-24..01-02` - month-day range, both days included, may wrap around the new year
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeWindow {
+    Weekly {
+        /// Empty means every day
+        days: Vec<Weekday>,
+        hours: Option<(NaiveTime, NaiveTime)>,
+    },
+    Yearly {
+        from: (u32, u32),
+        until: (u32, u32),
+    },
+}
+
+impl TimeWindow {
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        match self {
+            TimeWindow::Weekly { days, hours } => {
+                let on_day = |date: NaiveDate| days.is_empty() || days.contains(&date.weekday());
+                match hours {
+                    None => on_day(at.date()),
+                    Some((start, end)) if start < end => {
+                        on_day(at.date()) && *start <= at.time() && at.time() < *end
+                    }
+                    Some((start, end)) => {
+                        (on_day(at.date()) && *start <= at.time())
+                            || (on_day(at.date() - TimeDelta::days(1)) && at.time() < *end)
+                    }
+                }
+            }
+            TimeWindow::Yearly { from, until } => {
+                let day = (at.month(), at.day());
+                if from <= until {
+                    *from <= day && day <= *until
+                } else {
+                    *from <= day || day <= *until
+                }
+            }
+        }
+    }
+
+    /// `;` separated windows, empty text means none.
+    pub fn parse_list(s: &str) -> Result<Vec<TimeWindow>, String> {
+        s.split(';')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(TimeWindow::from_str)
+            .collect()
+    }
+
+    pub fn fmt_list(windows: &[TimeWindow]) -> String {
+        windows
+            .iter()
+            .map(|w| w.to_string())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| format!("Invalid time window \"{s}\": {reason}");
+
+        if let Some((from, until)) = s.trim().split_once("..") {
+            let month_day = |text: &str| {
+                NaiveDate::parse_from_str(&format!("2000-{}", text.trim()), "%Y-%m-%d")
+                    .map(|d| (d.month(), d.day()))
+                    .map_err(|_| invalid("expected MM-DD..MM-DD"))
+            };
+            return Ok(TimeWindow::Yearly {
+                from: month_day(from)?,
+                until: month_day(until)?,
+            });
+        }
+
+        let (mut days, mut hours) = (Vec::new(), None);
+        for token in s.split_whitespace() {
+            if let Some((start, end)) = token.split_once('-').filter(|_| token.contains(':')) {
+                let time = |text: &str| {
+                    NaiveTime::parse_from_str(text, "%H:%M")
+                        .map_err(|_| invalid("expected HH:MM-HH:MM hours"))
+                };
+                let (start, end) = (time(start)?, time(end)?);
+                if start == end || hours.is_some() {
+                    return Err(invalid("expected a single non empty hour range"));
+                }
+                hours = Some((start, end));
+            } else {
+                days.extend(parse_days(token).map_err(|_| invalid("expected days like Mon-Fri"))?);
+            }
+        }
+        if days.is_empty() && hours.is_none() {
+            return Err(invalid("expected days and/or hours"));
+        }
+
+        days.sort_by_key(Weekday::num_days_from_monday);
+        days.dedup();
+        Ok(TimeWindow::Weekly { days, hours })
+    }
+}
+
+/// `Mon-Fri`, `Sat,Sun`, `Fri-Mon` (ranges wrap around the week)
+fn parse_days(token: &str) -> Result<Vec<Weekday>, chrono::ParseWeekdayError> {
+    let mut days = Vec::new();
+    for part in token.split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (mut day, last) = (Weekday::from_str(first)?, Weekday::from_str(last)?);
+                days.push(day);
+                while day != last {
+                    day = day.succ();
+                    days.push(day);
+                }
+            }
+            None => days.push(Weekday::from_str(part)?),
+        }
+    }
+    Ok(days)
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeWindow::Weekly { days, hours } => {
+                let mut parts = Vec::new();
+                if !days.is_empty() {
+                    parts.push(fmt_days(days));
+                }
+                if let Some((start, end)) = hours {
+                    parts.push(format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")));
+                }
+                write!(f, "{}", parts.join(" "))
+            }
+            TimeWindow::Yearly { from, until } => write!(
+                f,
+                "{:02}-{:02}..{:02}-{:02}",
+                from.0, from.1, until.0, until.1
+            ),
+        }
+    }
+}
+
+/// Runs of 3 or more consecutive days are written as ranges: `Mon-Wed,Fri`
+fn fmt_days(days: &[Weekday]) -> String {
+    let mut runs: Vec<Vec<Weekday>> = Vec::new();
+    for day in days {
+        match runs.last_mut() {
+            Some(run) if run.last().is_some_and(|last| last.succ() == *day) => run.push(*day),
+            _ => runs.push(vec![*day]),
+        }
+    }
+    runs.iter()
+        .map(|run| match run.as_slice() {
+            [first, .., last] if run.len() >= 3 => format!("{first}-{last}"),
+            run => run
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// `2025-11-01` (midnight) or `2025-11-01T06:30[:00]`, empty text means unbounded.
+pub fn parse_optional_datetime(s: &str) -> Result<Option<NaiveDateTime>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M"))
+        .or_else(|_| NaiveDate::parse_from_str(s, "%Y-%m-%d").map(|d| d.and_time(NaiveTime::MIN)))
+        .map(Some)
+        .map_err(|_| format!("Invalid date \"{s}\""))
+}
+
+pub fn fmt_optional_datetime(value: Option<NaiveDateTime>) -> String {
+    match value {
+        None => String::new(),
+        Some(at) if at.time() == NaiveTime::MIN => at.format("%Y-%m-%d").to_string(),
+        Some(at) if at.second() == 0 => at.format("%Y-%m-%dT%H:%M").to_string(),
+        Some(at) => at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        parse_optional_datetime(&format!("{date}T{time}"))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_windows_round_trip() {
+        let text = "Mon-Fri 08:00-18:00; Sat,Sun; 22:00-06:00; Fri-Mon; 12-24..01-02";
+        let windows = TimeWindow::parse_list(text).unwrap();
+
+        assert_eq!(
+            TimeWindow::fmt_list(&windows),
+            "Mon-Fri 08:00-18:00; Sat,Sun; 22:00-06:00; Mon,Fri-Sun; 12-24..01-02"
+        );
+        assert_eq!(
+            TimeWindow::parse_list(&TimeWindow::fmt_list(&windows)).unwrap(),
+            windows
+        );
+        assert!(TimeWindow::parse_list("Mon-Fri 8-18").is_err());
+        assert!(TimeWindow::parse_list("Someday").is_err());
+        assert!(TimeWindow::parse_list("13-01..01-02").is_err());
+    }
+
+    #[test]
+    fn test_window_contains() {
+        // 2025-02-14 is a Friday
+        let office_hours = TimeWindow::from_str("Mon-Fri 08:00-18:00").unwrap();
+        assert!(office_hours.contains(at("2025-02-14", "08:00")));
+        assert!(!office_hours.contains(at("2025-02-14", "18:00")));
+        assert!(!office_hours.contains(at("2025-02-15", "12:00")));
+
+        let friday_night = TimeWindow::from_str("Fri 22:00-06:00").unwrap();
+        assert!(friday_night.contains(at("2025-02-14", "23:00")));
+        assert!(friday_night.contains(at("2025-02-15", "05:59")));
+        assert!(!friday_night.contains(at("2025-02-14", "05:00")));
+
+        let holidays = TimeWindow::from_str("12-24..01-02").unwrap();
+        assert!(holidays.contains(at("2025-12-31", "12:00")));
+        assert!(holidays.contains(at("2026-01-02", "23:59")));
+        assert!(!holidays.contains(at("2026-01-03", "00:00")));
+    }
+
+    #[test]
+    fn test_schedule_is_active_at() {
+        let schedule = Schedule {
+            effective_from: parse_optional_datetime("2025-11-01").unwrap(),
+            effective_until: None,
+            active_windows: vec![],
+            inactive_windows: TimeWindow::parse_list("12-24..01-02").unwrap(),
+        };
+
+        assert!(!schedule.is_active_at(at("2025-10-31", "23:59")));
+        assert!(schedule.is_active_at(at("2025-11-01", "00:00")));
+        assert!(!schedule.is_active_at(at("2025-12-25", "10:00")));
+        assert!(schedule.is_active_at(at("2026-01-03", "10:00")));
+        assert!(Schedule::default().is_active_at(at("2025-01-01", "00:00")));
+        assert_eq!(
+            fmt_optional_datetime(schedule.effective_from),
+            "2025-11-01".to_string()
+        );
+    }
+}
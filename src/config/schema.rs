@@ -0,0 +1,337 @@
+use super::config::{AttributeKind, Rule};
+use super::schedule::SCHEDULE_ATTRIBUTES;
+use super::validator::{GROUP_ATTRIBUTES, RULE_ATTRIBUTES, VALIDATION_RULE_ATTRIBUTES};
+use crate::library::RuleType;
+
+use serde_json::{json, Map, Value};
+
+// Patterns of the text values `csv_ids` / `optional_i32` / `optional_bool` / `parse_optional_datetime` accept
+const BOOL_PATTERN: &str = r"\s*([Tt][Rr][Uu][Ee]|[Ff][Aa][Ll][Ss][Ee])?\s*";
+const IDS_PATTERN: &str = r"(-?[0-9]+)?(,(-?[0-9]+)?)*";
+const INT_PATTERN: &str = r"\s*(-?[0-9]+)?\s*";
+const DATE_TIME_PATTERN: &str =
+    r"\s*([0-9]{4}-[0-9]{2}-[0-9]{2}(T[0-9]{2}:[0-9]{2}(:[0-9]{2})?)?)?\s*";
+
+/// `;` separated `TimeWindow`s (week day names are not checked).
+fn windows_pattern() -> String {
+    let days = r"[A-Za-z]+(-[A-Za-z]+)?(,[A-Za-z]+(-[A-Za-z]+)?)*";
+    let hours = r"[0-9]{2}:[0-9]{2}-[0-9]{2}:[0-9]{2}";
+    let window =
+        format!(r"({days}(\s+{hours})?|{hours}|[0-9]{{2}}-[0-9]{{2}}\.\.[0-9]{{2}}-[0-9]{{2}})");
+    format!(r"\s*({window}(\s*;\s*{window})*)?\s*")
+}
+
+const GENERATED: &str =
+    "Generated from the Config model by the `export_schema` binary, do not edit by hand.";
+
+/// Attributes of the `<ValidationRule>` and of its `<Rule>` entries that apply to `rule_type`.
+fn rule_attributes(rule_type: &RuleType) -> (Vec<&'static str>, Vec<&'static str>) {
+    let rule = Rule::empty(rule_type);
+    let mut parent = vec!["Type", "Enabled"];
+    parent.extend(rule.inherited_attributes());
+    parent.extend(SCHEDULE_ATTRIBUTES);
+    let entry = rule
+        .attributes()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    (parent, entry)
+}
+
+/// XML Schema (1.0) of the XML config. </br>
+/// XSD 1.0 can't make the allowed attributes depend on `Type`, so every attribute is allowed on every
+/// rule - the attributes that apply to each rule type are listed in the `RuleType` documentation.
+pub fn xml_schema() -> String {
+    let attribute = |name: &str, required: bool| {
+        let kind = match AttributeKind::of(name) {
+            AttributeKind::RuleType => "RuleType",
+            AttributeKind::Bool => "Bool",
+            AttributeKind::Ids => "Ids",
+            AttributeKind::Int => "Int",
+            AttributeKind::DateTime => "DateTime",
+            AttributeKind::Windows => "Windows",
+            AttributeKind::Text => "xs:string",
+        };
+        let usage = if required { "required" } else { "optional" };
+        format!(r#"<xs:attribute name="{name}" type="{kind}" use="{usage}"/>"#)
+    };
+    let attributes = |names: &[&str], required: &[&str], indent: &str| {
+        names
+            .iter()
+            .map(|name| format!("{indent}{}", attribute(name, required.contains(name))))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let rule_types = RuleType::ALL
+        .iter()
+        .map(|rule_type| {
+            let (parent, entry) = rule_attributes(rule_type);
+            format!(
+                r#"         <xs:enumeration value="{rule_type:?}">
+            <xs:annotation>
+               <xs:documentation>Group "{}". ValidationRule attributes: {}. Rule attributes: {}.</xs:documentation>
+            </xs:annotation>
+         </xs:enumeration>"#,
+                rule_type.group(),
+                parent.join(", "),
+                entry.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- {GENERATED} -->
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+   <xs:element name="config">
+      <xs:complexType>
+         <xs:sequence>
+            <xs:element name="ValidationRules">
+               <xs:complexType>
+                  <xs:sequence>
+                     <xs:element name="Groups">
+                        <xs:complexType>
+                           <xs:sequence>
+                              <xs:element name="ValidationRulesGroup" type="ValidationRulesGroup" maxOccurs="unbounded"/>
+                           </xs:sequence>
+                        </xs:complexType>
+                     </xs:element>
+                  </xs:sequence>
+               </xs:complexType>
+            </xs:element>
+         </xs:sequence>
+      </xs:complexType>
+   </xs:element>
+   <xs:complexType name="ValidationRulesGroup">
+      <xs:sequence>
+         <xs:element name="ValidationRule" type="ValidationRule" maxOccurs="unbounded"/>
+      </xs:sequence>
+{group_attributes}
+   </xs:complexType>
+   <xs:complexType name="ValidationRule">
+      <xs:sequence>
+         <xs:element name="Rules">
+            <xs:complexType>
+               <xs:sequence>
+                  <xs:element name="Rule" type="Rule" minOccurs="0" maxOccurs="unbounded"/>
+               </xs:sequence>
+            </xs:complexType>
+         </xs:element>
+      </xs:sequence>
+{validation_rule_attributes}
+   </xs:complexType>
+   <xs:complexType name="Rule">
+{rule_attributes}
+   </xs:complexType>
+   <xs:simpleType name="RuleType">
+      <xs:restriction base="xs:string">
+{rule_types}
+      </xs:restriction>
+   </xs:simpleType>
+   <xs:simpleType name="Bool">
+      <xs:restriction base="xs:string">
+         <xs:pattern value="{BOOL_PATTERN}"/>
+      </xs:restriction>
+   </xs:simpleType>
+   <xs:simpleType name="Ids">
+      <xs:restriction base="xs:string">
+         <xs:pattern value="{IDS_PATTERN}"/>
+      </xs:restriction>
+   </xs:simpleType>
+   <xs:simpleType name="Int">
+      <xs:restriction base="xs:string">
+         <xs:pattern value="{INT_PATTERN}"/>
+      </xs:restriction>
+   </xs:simpleType>
+   <xs:simpleType name="DateTime">
+      <xs:restriction base="xs:string">
+         <xs:pattern value="{DATE_TIME_PATTERN}"/>
+      </xs:restriction>
+   </xs:simpleType>
+   <xs:simpleType name="Windows">
+      <xs:restriction base="xs:string">
+         <xs:pattern value="{windows_pattern}"/>
+      </xs:restriction>
+   </xs:simpleType>
+</xs:schema>
+"#,
+        group_attributes = attributes(GROUP_ATTRIBUTES, &["Group"], "      "),
+        validation_rule_attributes =
+            attributes(VALIDATION_RULE_ATTRIBUTES, &["Type", "Enabled"], "      "),
+        rule_attributes = attributes(RULE_ATTRIBUTES, &[], "      "),
+        windows_pattern = windows_pattern(),
+    )
+}
+
+/// JSON Schema (2020-12) of the JSON config (`ConfigFormat::Json`). </br>
+/// Unlike the XSD, every rule type only allows its own attributes.
+pub fn json_schema() -> String {
+    let value = |name: &str| match AttributeKind::of(name) {
+        AttributeKind::RuleType | AttributeKind::Text => json!({ "type": "string" }),
+        AttributeKind::Bool => json!({ "$ref": "#/$defs/Bool" }),
+        AttributeKind::Ids => json!({ "$ref": "#/$defs/Ids" }),
+        AttributeKind::Int => json!({ "$ref": "#/$defs/Int" }),
+        AttributeKind::DateTime => json!({ "$ref": "#/$defs/DateTime" }),
+        AttributeKind::Windows => json!({ "$ref": "#/$defs/Windows" }),
+    };
+
+    let mut defs = Map::new();
+    let mut rule_refs = Vec::new();
+    for rule_type in &RuleType::ALL {
+        let name = format!("{rule_type:?}");
+        let (parent, entry) = rule_attributes(rule_type);
+
+        let mut properties = Map::new();
+        for attribute in parent {
+            let schema = match attribute {
+                "Type" => json!({ "const": name }),
+                attribute => value(attribute),
+            };
+            properties.insert(attribute.to_string(), schema);
+        }
+        properties.insert(
+            "Rules".to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "Rule": { "type": "array", "items": { "$ref": format!("#/$defs/{name}Rule") } }
+                },
+                "additionalProperties": false
+            }),
+        );
+        let entry_properties: Map<String, Value> = entry
+            .into_iter()
+            .map(|attribute| (attribute.to_string(), value(attribute)))
+            .collect();
+
+        defs.insert(
+            name.clone(),
+            json!({
+                "description": format!("Group \"{}\"", rule_type.group()),
+                "type": "object",
+                "required": ["Type", "Enabled", "Rules"],
+                "properties": properties,
+                "additionalProperties": false
+            }),
+        );
+        defs.insert(
+            format!("{name}Rule"),
+            json!({
+                "type": "object",
+                "properties": entry_properties,
+                "additionalProperties": false
+            }),
+        );
+        rule_refs.push(json!({ "$ref": format!("#/$defs/{name}") }));
+    }
+
+    defs.insert(
+        "ValidationRulesGroup".to_string(),
+        json!({
+            "type": "object",
+            "required": ["Group", "ValidationRule"],
+            "properties": {
+                "Group": { "type": "string" },
+                "ValidationRule": { "type": "array", "items": { "oneOf": rule_refs } }
+            },
+            "additionalProperties": false
+        }),
+    );
+    defs.insert(
+        "Bool".to_string(),
+        json!({ "anyOf": [
+            { "type": "boolean" },
+            { "type": "string", "pattern": format!("^{BOOL_PATTERN}$") }
+        ]}),
+    );
+    defs.insert(
+        "Ids".to_string(),
+        json!({ "anyOf": [
+            { "type": "array", "items": { "type": "integer" } },
+            { "type": "string", "pattern": format!("^{IDS_PATTERN}$") }
+        ]}),
+    );
+    defs.insert(
+        "Int".to_string(),
+        json!({ "anyOf": [
+            { "type": "integer" },
+            { "type": "string", "pattern": format!("^{INT_PATTERN}$") }
+        ]}),
+    );
+    defs.insert(
+        "DateTime".to_string(),
+        json!({ "type": "string", "pattern": format!("^{DATE_TIME_PATTERN}$") }),
+    );
+    defs.insert(
+        "Windows".to_string(),
+        json!({ "type": "string", "pattern": format!("^{}$", windows_pattern()) }),
+    );
+
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$comment": GENERATED,
+        "title": "Validation rules config",
+        "type": "object",
+        "required": ["ValidationRules"],
+        "properties": {
+            "ValidationRules": {
+                "type": "object",
+                "required": ["Groups"],
+                "properties": {
+                    "Groups": {
+                        "type": "object",
+                        "required": ["ValidationRulesGroup"],
+                        "properties": {
+                            "ValidationRulesGroup": {
+                                "type": "array",
+                                "items": { "$ref": "#/$defs/ValidationRulesGroup" }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "$defs": defs
+    });
+    serde_json::to_string_pretty(&schema).unwrap() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// The committed schemas are what editors and CI use, they must follow the model.
+    #[test]
+    fn test_committed_schemas_are_up_to_date() {
+        assert_eq!(
+            std::fs::read_to_string("schema/validator_config.xsd").unwrap(),
+            xml_schema()
+        );
+        assert_eq!(
+            std::fs::read_to_string("schema/validator_config.schema.json").unwrap(),
+            json_schema()
+        );
+    }
+
+    #[test]
+    fn test_rule_attributes() {
+        assert_eq!(
+            rule_attributes(&RuleType::SideJobPrevention),
+            (
+                vec![
+                    "Type",
+                    "Enabled",
+                    "PositionTypeIDs",
+                    "FromMatchStatusId",
+                    "EffectiveFrom",
+                    "EffectiveUntil",
+                    "ActiveWindows",
+                    "InactiveWindows"
+                ],
+                vec!["ForCandidateStatusIds", "Enforce"]
+            )
+        );
+    }
+}
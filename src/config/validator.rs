@@ -0,0 +1,285 @@
+use super::config::{csv_ids, optional_bool, optional_i32, AttributeKind, Location, Rule};
+use super::schedule::{parse_optional_datetime, TimeWindow};
+use crate::library::RuleType;
+
+use std::ops::Range;
+use std::str::FromStr;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+pub(crate) const GROUP_ATTRIBUTES: &[&str] = &["Group"];
+pub(crate) const VALIDATION_RULE_ATTRIBUTES: &[&str] = &[
+    "Type",
+    "Enabled",
+    "FallbackShiftStatusId",
+    "PositionTypeIDs",
+    "FromMatchStatusId",
+    "EffectiveFrom",
+    "EffectiveUntil",
+    "ActiveWindows",
+    "InactiveWindows",
+];
+pub(crate) const RULE_ATTRIBUTES: &[&str] = &[
+    "ForCandidateStatusIds",
+    "Enforce",
+    "ForShiftStatusIds",
+    "HoursAllowed",
+    "WithinXHours",
+    "Minutes",
+    "IfShiftEndReasonIds",
+    "ForTheNextXDays",
+];
+
+/// Walks the XML config and reports every bad attribute with its line/column,
+/// instead of stopping at the first error like `quick_xml::de::from_str` does. </br>
+/// Empty result means the config is syntactically valid.
+pub fn validate(xml: &str) -> Vec<Location> {
+    check(xml, false)
+}
+
+/// Overlays only carry the attributes they override, so apart from the attributes addressing
+/// a rule (`Group`, `Type` and `ForCandidateStatusIds`) nothing is required.
+pub fn validate_overlay(xml: &str) -> Vec<Location> {
+    check(xml, true)
+}
+
+fn check(xml: &str, overlay: bool) -> Vec<Location> {
+    let mut reader = Reader::from_str(xml);
+    let mut problems = Vec::new();
+    // `Type` of the enclosing `<ValidationRule>`, its `<Rule>` entries only take that type's attributes
+    let mut rule_type = None;
+
+    loop {
+        let tag_start = reader.buffer_position() as usize;
+        match reader.read_event() {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
+                if e.name().as_ref() == b"ValidationRule" {
+                    rule_type = e
+                        .try_get_attribute("Type")
+                        .ok()
+                        .flatten()
+                        .and_then(|attr| RuleType::from_str(&attr.unescape_value().ok()?).ok());
+                }
+                let tag_end = reader.buffer_position() as usize;
+                check_tag(
+                    xml,
+                    tag_start..tag_end,
+                    e,
+                    rule_type.as_ref(),
+                    overlay,
+                    &mut problems,
+                );
+            }
+            Ok(Event::End(ref e)) if e.name().as_ref() == b"ValidationRule" => rule_type = None,
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                let position = reader.error_position() as usize;
+                problems.push(location(xml, position, format!("Malformed XML: {e}")));
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    problems
+}
+
+fn check_tag(
+    xml: &str,
+    span: Range<usize>,
+    tag: &BytesStart,
+    rule_type: Option<&RuleType>,
+    overlay: bool,
+    problems: &mut Vec<Location>,
+) {
+    let rule_attributes: Vec<&str> = match rule_type {
+        Some(rule_type) => Rule::empty(rule_type)
+            .attributes()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect(),
+        None => RULE_ATTRIBUTES.to_vec(),
+    };
+    let (known, required): (&[&str], &[&str]) = match (tag.name().as_ref(), overlay) {
+        (b"ValidationRulesGroup", _) => (GROUP_ATTRIBUTES, &["Group"]),
+        (b"ValidationRule", false) => (VALIDATION_RULE_ATTRIBUTES, &["Type", "Enabled"]),
+        (b"ValidationRule", true) => (VALIDATION_RULE_ATTRIBUTES, &["Type"]),
+        (b"Rule", false) => (&rule_attributes, &[]),
+        (b"Rule", true) => (&rule_attributes, &["ForCandidateStatusIds"]),
+        _ => return,
+    };
+    let tag_name = String::from_utf8_lossy(tag.name().as_ref()).to_string();
+    let raw_tag = &xml[span.clone()];
+    let mut seen = Vec::new();
+
+    for attr in tag.attributes() {
+        let attr = match attr {
+            Ok(attr) => attr,
+            Err(e) => {
+                problems.push(location(xml, span.start, format!("<{tag_name}> {e}")));
+                continue;
+            }
+        };
+        let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+        let value = attr
+            .unescape_value()
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+
+        let message = match rule_type {
+            Some(rule_type) if tag_name == "Rule" && !known.contains(&key.as_str()) => Some(
+                format!("Unknown attribute '{key}' for {rule_type:?} <Rule>"),
+            ),
+            _ => check_attribute(known, &key, &value),
+        };
+        if let Some(message) = message {
+            let offset = span.start + attribute_offset(raw_tag, &key).unwrap_or(0);
+            problems.push(location(xml, offset, message));
+        }
+        seen.push(key);
+    }
+
+    for missing in required
+        .iter()
+        .filter(|name| !seen.iter().any(|s| s == *name))
+    {
+        problems.push(location(
+            xml,
+            span.start,
+            format!("<{tag_name}> is missing required attribute '{missing}'"),
+        ));
+    }
+}
+
+fn check_attribute(known: &[&str], key: &str, value: &str) -> Option<String> {
+    if !known.contains(&key) {
+        return Some(format!("Unknown attribute '{key}'"));
+    }
+
+    let expected = match AttributeKind::of(key) {
+        AttributeKind::RuleType => RuleType::from_str(value).err().map(|_| "a known rule type"),
+        AttributeKind::Bool => optional_bool(value).err().map(|_| "True or False"),
+        AttributeKind::Ids => csv_ids(value).err().map(|_| "comma separated integer ids"),
+        AttributeKind::Int => optional_i32(value).err().map(|_| "an integer"),
+        AttributeKind::DateTime => parse_optional_datetime(value)
+            .err()
+            .map(|_| "a date like 2025-11-01 or 2025-11-01T06:00"),
+        AttributeKind::Windows => TimeWindow::parse_list(value)
+            .err()
+            .map(|_| "time windows like \"Mon-Fri 08:00-18:00; 12-24..01-02\""),
+        AttributeKind::Text => None,
+    }?;
+
+    Some(format!(
+        "Invalid value \"{value}\" for '{key}', expected {expected}"
+    ))
+}
+
+/// Byte offset of `key=` inside the raw tag text (attribute names are preceded by whitespace).
+fn attribute_offset(raw_tag: &str, key: &str) -> Option<usize> {
+    raw_tag.match_indices(key).map(|(i, _)| i).find(|&i| {
+        let preceded_by_space = raw_tag[..i].ends_with(char::is_whitespace);
+        let followed_by_eq = raw_tag[i + key.len()..].trim_start().starts_with('=');
+        preceded_by_space && followed_by_eq
+    })
+}
+
+/// 1 indexed line and column of the byte offset
+pub(crate) fn location(xml: &str, offset: usize, message: String) -> Location {
+    let prefix = &xml[..offset.min(xml.len())];
+    let line = prefix.matches('\n').count() + 1;
+    let column = prefix.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+    Location {
+        message,
+        line,
+        column,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_valid_configs() {
+        for path in ["validator_config.xml", "test_config.xml"] {
+            let xml = std::fs::read_to_string(path).unwrap();
+            assert_eq!(validate(&xml), vec![]);
+        }
+    }
+
+    #[test]
+    fn test_all_problems_are_reported() {
+        let xml = r#"<config>
+  <ValidationRules>
+    <Groups>
+      <ValidationRulesGroup Group="Shift">
+        <ValidationRule Type="SideJobPrevention" PositionTypeIDs="a" FromMatchStatusId="c" Enabled="True">
+          <Rules>
+            <Rule ForCandidateStatusIds="1,x" Enforce="yes" />
+          </Rules>
+        </ValidationRule>
+        <ValidationRule Type="SideJobs" Minutes="5">
+          <Rules />
+        </ValidationRule>
+      </ValidationRulesGroup>
+    </Groups>
+  </ValidationRules>
+</config>"#;
+
+        let problems: Vec<String> = validate(xml).iter().map(|l| l.to_string()).collect();
+
+        assert_eq!(
+            problems,
+            vec![
+                "Error at line 5, column 50: Invalid value \"a\" for 'PositionTypeIDs', expected comma separated integer ids",
+                "Error at line 5, column 70: Invalid value \"c\" for 'FromMatchStatusId', expected an integer",
+                "Error at line 7, column 19: Invalid value \"1,x\" for 'ForCandidateStatusIds', expected comma separated integer ids",
+                "Error at line 7, column 47: Invalid value \"yes\" for 'Enforce', expected True or False",
+                "Error at line 10, column 25: Invalid value \"SideJobs\" for 'Type', expected a known rule type",
+                "Error at line 10, column 41: Unknown attribute 'Minutes'",
+                "Error at line 10, column 9: <ValidationRule> is missing required attribute 'Enabled'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attributes_of_other_rule_types() {
+        let xml = r#"<config>
+  <ValidationRules>
+    <Groups>
+      <ValidationRulesGroup Group="Shift">
+        <ValidationRule Type="SideJobPrevention" Enabled="True">
+          <Rules><Rule ForCandidateStatusIds="1" Minutes="5" /></Rules>
+        </ValidationRule>
+      </ValidationRulesGroup>
+      <ValidationRulesGroup Group="Candidate">
+        <ValidationRule Type="IndecisivePrevention" Enabled="True">
+          <Rules><Rule ForCandidateStatusIds="" HoursAllowed="8" ForTheNextXDays="2" /></Rules>
+        </ValidationRule>
+      </ValidationRulesGroup>
+    </Groups>
+  </ValidationRules>
+</config>"#;
+
+        let problems: Vec<String> = validate(xml).iter().map(|l| l.to_string()).collect();
+
+        assert_eq!(
+            problems,
+            vec![
+                "Error at line 6, column 50: Unknown attribute 'Minutes' for SideJobPrevention <Rule>",
+                "Error at line 11, column 49: Unknown attribute 'HoursAllowed' for IndecisivePrevention <Rule>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_xml() {
+        let problems = validate("<config>\n  <ValidationRules>\n</config>");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, 3);
+    }
+}
@@ -0,0 +1,135 @@
+use super::rule_definitions::ExhaustionPrevention;
+use crate::library::{Action, Shift, ValidationRequest};
+
+use chrono::{NaiveDateTime, TimeDelta};
+
+/// Blocks a booking when, inside any `WithinXHours` long window touching the requested shift,
+/// the candidate would work more than `HoursAllowed`. </br>
+/// Only shifts in `ForShiftStatusIds` are counted (`FallbackShiftStatusId` when the list is empty).
+pub fn evaluate(rule: &ExhaustionPrevention, request: &ValidationRequest) -> Option<String> {
+    if request.action != Action::Book {
+        return None;
+    }
+    let (hours_allowed, within_x_hours) = (rule.hours_allowed?, rule.within_x_hours?);
+
+    let shift_status_ids: Vec<i32> = if rule.for_shift_status_ids.is_empty() {
+        rule.fallback_shift_status_id.into_iter().collect()
+    } else {
+        rule.for_shift_status_ids.clone()
+    };
+
+    let mut counted: Vec<&Shift> = request
+        .candidate
+        .shifts
+        .iter()
+        .filter(|shift| shift.id != request.shift.id)
+        .filter(|shift| {
+            shift_status_ids.is_empty() || shift_status_ids.contains(&shift.shift_status_id)
+        })
+        .collect();
+    counted.push(&request.shift);
+
+    let window = TimeDelta::hours(within_x_hours as i64);
+    let worked = busiest_window(&counted, &request.shift, window);
+
+    if worked > TimeDelta::hours(hours_allowed as i64) {
+        return Some(format!(
+            "Booking this shift adds up to {:.1} hours within {} hours, only {} hours are allowed.",
+            worked.num_minutes() as f64 / 60.0,
+            within_x_hours,
+            hours_allowed
+        ));
+    }
+
+    None
+}
+
+/// Most time worked inside a single `window` that overlaps `proposed`. </br>
+/// The maximum is always reached by a window that starts at some shift start or ends at some shift end,
+/// so only those candidates are checked. Works on instants, shifts crossing midnight need no special care.
+fn busiest_window(shifts: &[&Shift], proposed: &Shift, window: TimeDelta) -> TimeDelta {
+    shifts
+        .iter()
+        .flat_map(|shift| [shift.start, shift.end - window])
+        .filter(|from| *from < proposed.end && proposed.start < *from + window)
+        .map(|from| worked_between(shifts, from, from + window))
+        .max()
+        .unwrap_or_default()
+}
+
+fn worked_between(shifts: &[&Shift], from: NaiveDateTime, to: NaiveDateTime) -> TimeDelta {
+    shifts
+        .iter()
+        .map(|shift| shift.end.min(to) - shift.start.max(from))
+        .filter(|worked| *worked > TimeDelta::zero())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::fixtures::{at, request, rule_params, shift};
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = r#"
+        <ValidationRule Type="ExhaustionPrevention" FallbackShiftStatusId="1" Enabled="True">
+            <Rules>
+                <Rule ForCandidateStatusIds="" ForShiftStatusIds="" HoursAllowed="16" WithinXHours="24" Enforce="True" />
+            </Rules>
+        </ValidationRule>"#;
+
+    fn params(xml: &str) -> ExhaustionPrevention {
+        rule_params!(ExhaustionPrevention, xml)
+    }
+
+    fn check(history: Vec<Shift>, proposed: Shift) -> Option<String> {
+        evaluate(&params(XML), &request(history, proposed, Action::Book))
+    }
+
+    #[test]
+    fn test_exceeding_hours_is_rejected() {
+        assert_eq!(
+            check(vec![shift(2, at(10, 6), 10)], shift(1, at(10, 18), 8)).unwrap(),
+            "Booking this shift adds up to 18.0 hours within 24 hours, only 16 hours are allowed."
+        );
+    }
+
+    #[test]
+    fn test_shift_crossing_midnight_is_counted() {
+        // 22:00 - 08:00 the next day followed by 12:00 - 20:00
+        assert!(check(vec![shift(2, at(10, 22), 10)], shift(1, at(11, 12), 8)).is_some());
+    }
+
+    #[test]
+    fn test_hours_outside_window_pass() {
+        assert_eq!(
+            check(vec![shift(2, at(10, 0), 10)], shift(1, at(11, 4), 8)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_shift_status_filter() {
+        // Only FallbackShiftStatusId="1" is counted
+        let mut cancelled = shift(2, at(10, 6), 10);
+        cancelled.shift_status_id = 4;
+        assert_eq!(check(vec![cancelled], shift(1, at(10, 18), 8)), None);
+    }
+
+    #[test]
+    fn test_missing_thresholds_pass() {
+        let params = params(
+            r#"<ValidationRule Type="ExhaustionPrevention" Enabled="True">
+                  <Rules>
+                     <Rule ForCandidateStatusIds="" ForShiftStatusIds="" HoursAllowed="" WithinXHours="" Enforce="True" />
+                  </Rules>
+               </ValidationRule>"#,
+        );
+        let request = request(
+            vec![shift(2, at(10, 0), 20)],
+            shift(1, at(10, 20), 4),
+            Action::Book,
+        );
+        assert_eq!(evaluate(&params, &request), None);
+    }
+}
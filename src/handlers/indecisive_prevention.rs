@@ -0,0 +1,77 @@
+use super::rule_definitions::IndecisivePrevention;
+use crate::library::{Action, ValidationRequest};
+
+use chrono::TimeDelta;
+
+/// Blocks new bookings for `ForTheNextXDays` after one of the candidate's shifts ended
+/// with any of the `IfShiftEndReasonIds`.
+pub fn evaluate(rule: &IndecisivePrevention, request: &ValidationRequest) -> Option<String> {
+    if request.action != Action::Book || rule.if_shift_end_reason_ids.is_empty() {
+        return None;
+    }
+    let cooldown = TimeDelta::days(rule.for_the_next_x_days? as i64);
+
+    request
+        .candidate
+        .shifts
+        .iter()
+        .filter(|shift| {
+            shift
+                .end_reason_id
+                .is_some_and(|reason| rule.if_shift_end_reason_ids.contains(&reason))
+        })
+        .filter(|shift| {
+            shift.end <= request.requested_at && request.requested_at < shift.end + cooldown
+        })
+        .max_by_key(|shift| shift.end)
+        .map(|shift| {
+            format!(
+                "Shift {} ended with reason {}, new shifts cannot be booked until {}.",
+                shift.id,
+                shift.end_reason_id.unwrap_or_default(),
+                shift.end + cooldown
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::fixtures::{at, request, rule_params, shift};
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = r#"
+        <ValidationRule Type="IndecisivePrevention" Enabled="True">
+            <Rules>
+                <Rule ForCandidateStatusIds="" IfShiftEndReasonIds="3,4" Enforce="True" ForTheNextXDays="2" />
+            </Rules>
+        </ValidationRule>"#;
+
+    fn check(end_reason_id: Option<i32>, requested_at_day: u32) -> Option<String> {
+        let mut ended = shift(2, at(10, 8), 8);
+        ended.end_reason_id = end_reason_id;
+        let mut request = request(vec![ended], shift(1, at(20, 8), 8), Action::Book);
+        request.requested_at = at(requested_at_day, 18);
+
+        evaluate(&rule_params!(IndecisivePrevention, XML), &request)
+    }
+
+    #[test]
+    fn test_booking_within_cooldown_is_rejected() {
+        assert_eq!(
+            check(Some(4), 11).unwrap(),
+            "Shift 2 ended with reason 4, new shifts cannot be booked until 2025-02-12 16:00:00."
+        );
+    }
+
+    #[test]
+    fn test_booking_after_cooldown_passes() {
+        assert_eq!(check(Some(4), 12), None);
+    }
+
+    #[test]
+    fn test_other_end_reasons_pass() {
+        assert_eq!(check(Some(1), 11), None);
+        assert_eq!(check(None, 11), None);
+    }
+}
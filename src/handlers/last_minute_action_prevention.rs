@@ -0,0 +1,111 @@
+use super::rule_definitions::{
+    LastMinuteActionPreventionForBooking, LastMinuteActionPreventionForCanceling,
+};
+use crate::library::{Action, ValidationRequest};
+
+use chrono::TimeDelta;
+
+pub fn evaluate_booking(
+    rule: &LastMinuteActionPreventionForBooking,
+    request: &ValidationRequest,
+) -> Option<String> {
+    evaluate(Action::Book, rule.minutes, request)
+}
+
+pub fn evaluate_canceling(
+    rule: &LastMinuteActionPreventionForCanceling,
+    request: &ValidationRequest,
+) -> Option<String> {
+    evaluate(Action::Cancel, rule.minutes, request)
+}
+
+/// Rejects `action` when it is requested less than `minutes` before the shift starts
+/// (or after it already started).
+fn evaluate(action: Action, minutes: Option<i32>, request: &ValidationRequest) -> Option<String> {
+    if request.action != action {
+        return None;
+    }
+    let minutes = minutes?;
+
+    if request.shift.start - request.requested_at >= TimeDelta::minutes(minutes as i64) {
+        return None;
+    }
+
+    let verb = match action {
+        Action::Book => "booked",
+        Action::Cancel => "self-cancelled",
+    };
+    Some(format!(
+        "A shift that starts within {} minutes cannot be {}, please call your Local Office for assistance.",
+        minutes, verb
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::fixtures::{at, request, rule_params, shift};
+    use pretty_assertions::assert_eq;
+
+    fn xml(rule_type: &str, minutes: &str) -> String {
+        format!(
+            r#"<ValidationRule Type="{rule_type}" Enabled="True">
+                  <Rules>
+                     <Rule ForCandidateStatusIds="" Minutes="{minutes}" Enforce="True" />
+                  </Rules>
+               </ValidationRule>"#
+        )
+    }
+
+    fn booking(minutes: &str) -> LastMinuteActionPreventionForBooking {
+        rule_params!(
+            LastMinuteActionPreventionForBooking,
+            &xml("LastMinuteActionPreventionForBooking", minutes)
+        )
+    }
+
+    fn canceling(minutes: &str) -> LastMinuteActionPreventionForCanceling {
+        rule_params!(
+            LastMinuteActionPreventionForCanceling,
+            &xml("LastMinuteActionPreventionForCanceling", minutes)
+        )
+    }
+
+    fn request_at(action: Action, minutes_before_start: i64) -> ValidationRequest {
+        let mut request = request(vec![], shift(1, at(10, 8), 8), action);
+        request.requested_at = request.shift.start - TimeDelta::minutes(minutes_before_start);
+        request
+    }
+
+    #[test]
+    fn test_last_minute_cancel_is_rejected() {
+        assert_eq!(
+            evaluate_canceling(&canceling("60"), &request_at(Action::Cancel, 45)).unwrap(),
+            "A shift that starts within 60 minutes cannot be self-cancelled, please call your Local Office for assistance."
+        );
+    }
+
+    #[test]
+    fn test_last_minute_booking_is_rejected() {
+        assert_eq!(
+            evaluate_booking(&booking("30"), &request_at(Action::Book, -10)).unwrap(),
+            "A shift that starts within 30 minutes cannot be booked, please call your Local Office for assistance."
+        );
+    }
+
+    #[test]
+    fn test_action_outside_window_passes() {
+        assert_eq!(
+            evaluate_canceling(&canceling("60"), &request_at(Action::Cancel, 60)),
+            None
+        );
+        assert_eq!(
+            evaluate_booking(&booking("60"), &request_at(Action::Cancel, 5)),
+            None
+        );
+        assert_eq!(
+            evaluate_canceling(&canceling(""), &request_at(Action::Cancel, 5)),
+            None
+        );
+    }
+}
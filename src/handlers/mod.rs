@@ -0,0 +1,138 @@
+pub mod rule_definitions;
+
+mod exhaustion_prevention;
+mod indecisive_prevention;
+mod last_minute_action_prevention;
+mod side_job_prevention;
+
+use crate::config::prelude::*;
+use crate::library::{rule_validation_error::RuleValidationError, ValidationRequest};
+
+/// Runs a single configured rule against the request. </br>
+/// Returns `None` when the rule passes or when none of its `<Rule>` entries targets the candidate status.
+pub fn evaluate(
+    settings: &ValidationRuleSettings,
+    request: &ValidationRequest,
+) -> Option<RuleValidationError> {
+    let rule = settings
+        .rules
+        .for_candidate_status(request.candidate.status_id)?;
+
+    let violation: Option<String> = match rule {
+        Rule::SideJobPrevention(params) => side_job_prevention::evaluate(params, request),
+        Rule::IndecisivePrevention(params) => indecisive_prevention::evaluate(params, request),
+        Rule::ExhaustionPrevention(params) => exhaustion_prevention::evaluate(params, request),
+        Rule::LastMinuteActionPreventionForBooking(params) => {
+            last_minute_action_prevention::evaluate_booking(params, request)
+        }
+        Rule::LastMinuteActionPreventionForCanceling(params) => {
+            last_minute_action_prevention::evaluate_canceling(params, request)
+        }
+    };
+
+    violation.map(|message| {
+        RuleValidationError::new(&settings.rule_type, message, tag(request), rule.enforce())
+    })
+}
+
+fn tag(request: &ValidationRequest) -> String {
+    format!(
+        "CandidateId {} / ShiftId {}",
+        request.candidate.id, request.shift.id
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::config::prelude::*;
+    use crate::library::{Action, Candidate, Shift, ValidationRequest};
+    use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+    /// February 2025 at the given day and hour.
+    pub fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 2, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    pub fn shift(id: i32, start: NaiveDateTime, hours: i64) -> Shift {
+        Shift {
+            id,
+            start,
+            end: start + TimeDelta::hours(hours),
+            position_type_id: 1,
+            match_status_id: 0,
+            shift_status_id: 1,
+            end_reason_id: None,
+        }
+    }
+
+    /// Request made a week before the shift starts by candidate 7 (status 1).
+    pub fn request(history: Vec<Shift>, shift: Shift, action: Action) -> ValidationRequest {
+        ValidationRequest {
+            tenant_id: None,
+            candidate: Candidate {
+                id: 7,
+                status_id: 1,
+                shifts: history,
+            },
+            requested_at: shift.start - TimeDelta::days(7),
+            shift,
+            action,
+        }
+    }
+
+    pub fn settings(xml: &str) -> ValidationRuleSettings {
+        quick_xml::de::from_str(xml).unwrap()
+    }
+
+    /// First `<Rule>` of the `<ValidationRule>` xml.
+    pub fn rule(xml: &str) -> Rule {
+        settings(xml).rules.rules.remove(0)
+    }
+
+    /// Typed parameters of the first `<Rule>`, e.g. `rule_params!(SideJobPrevention, XML)`.
+    macro_rules! rule_params {
+        ($rule_type:ident, $xml:expr) => {
+            match $crate::handlers::fixtures::rule($xml) {
+                $crate::config::prelude::Rule::$rule_type(params) => params,
+                other => panic!("expected a {} rule, got {other:?}", stringify!($rule_type)),
+            }
+        };
+    }
+    pub(crate) use rule_params;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::{at, request, settings, shift};
+    use super::*;
+    use crate::library::Action;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_enforce_decides_error_or_warning() {
+        let xml = r#"
+            <ValidationRule Type="SideJobPrevention" PositionTypeIDs="" Enabled="True">
+                <Rules>
+                    <Rule ForCandidateStatusIds="1" Enforce="True" />
+                    <Rule ForCandidateStatusIds="" Enforce="False" />
+                </Rules>
+            </ValidationRule>"#;
+        let mut request = request(
+            vec![shift(2, at(10, 8), 8)],
+            shift(1, at(10, 12), 8),
+            Action::Book,
+        );
+
+        let error = evaluate(&settings(xml), &request).unwrap();
+        assert_eq!(error.rule_name(), "SideJobPrevention");
+        assert_eq!(error.tag(), "CandidateId 7 / ShiftId 1");
+        assert_eq!(error.success(), false);
+
+        request.candidate.status_id = 4;
+        let warning = evaluate(&settings(xml), &request).unwrap();
+        assert_eq!(warning.success(), true);
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct IndecisivePrevention {
     pub for_candidate_status_ids: Vec<i32>,
     pub if_shift_end_reason_ids: Vec<i32>,
@@ -6,31 +6,33 @@ pub struct IndecisivePrevention {
     pub enforce: Option<bool>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct SideJobPrevention {
     pub for_candidate_status_ids: Vec<i32>,
-    pub role_type_id: Option<i32>,
+    pub position_type_ids: Vec<i32>,
+    pub from_match_status_id: Option<i32>,
     pub enforce: Option<bool>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct LastMinuteActionPreventionForBooking {
     pub for_candidate_status_ids: Vec<i32>,
     pub minutes: Option<i32>,
     pub enforce: Option<bool>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct LastMinuteActionPreventionForCanceling {
     pub for_candidate_status_ids: Vec<i32>,
     pub minutes: Option<i32>,
     pub enforce: Option<bool>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct ExhaustionPrevention {
     pub for_candidate_status_ids: Vec<i32>,
     pub for_shift_status_ids: Vec<i32>,
+    pub fallback_shift_status_id: Option<i32>,
     pub hours_allowed: Option<i32>,
     pub within_x_hours: Option<i32>,
     pub enforce: Option<bool>,
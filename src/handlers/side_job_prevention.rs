@@ -0,0 +1,106 @@
+use super::rule_definitions::SideJobPrevention;
+use crate::library::{Action, Shift, ValidationRequest};
+
+/// Blocks a booking when the candidate already holds a shift that overlaps (or falls on the same day as)
+/// the requested one, in one of the configured `PositionTypeIDs`, at or above `FromMatchStatusId`.
+pub fn evaluate(rule: &SideJobPrevention, request: &ValidationRequest) -> Option<String> {
+    if request.action != Action::Book {
+        return None;
+    }
+
+    let from_match_status_id = rule.from_match_status_id.unwrap_or(0);
+    let is_conflicting = |shift: &&Shift| {
+        shift.id != request.shift.id
+            && (rule.position_type_ids.is_empty()
+                || rule.position_type_ids.contains(&shift.position_type_id))
+            && shift.match_status_id >= from_match_status_id
+            && (shift.overlaps(&request.shift) || shift.start.date() == request.shift.start.date())
+    };
+
+    request
+        .candidate
+        .shifts
+        .iter()
+        .find(is_conflicting)
+        .map(|conflict| {
+            format!(
+                "Candidate already holds shift {} on {} in a conflicting position type, side jobs are not allowed.",
+                conflict.id,
+                conflict.start.date()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::fixtures::{at, request, rule_params, shift};
+    use pretty_assertions::assert_eq;
+
+    fn check(request: &ValidationRequest) -> Option<String> {
+        evaluate(&rule_params!(SideJobPrevention, XML), request)
+    }
+
+    const XML: &str = r#"
+        <ValidationRule Type="SideJobPrevention" PositionTypeIDs="2" FromMatchStatusId="1" Enabled="True">
+            <Rules>
+                <Rule ForCandidateStatusIds="" Enforce="True" />
+            </Rules>
+        </ValidationRule>"#;
+
+    #[test]
+    fn test_overlapping_shift_is_rejected() {
+        let mut existing = shift(2, at(10, 12), 8);
+        existing.position_type_id = 2;
+        existing.match_status_id = 1;
+        let request = request(vec![existing], shift(1, at(10, 16), 8), Action::Book);
+
+        assert_eq!(
+            check(&request).unwrap(),
+            "Candidate already holds shift 2 on 2025-02-10 in a conflicting position type, side jobs are not allowed."
+        );
+    }
+
+    #[test]
+    fn test_same_day_shift_is_rejected() {
+        let mut existing = shift(2, at(10, 6), 4);
+        existing.position_type_id = 2;
+        existing.match_status_id = 3;
+        let request = request(vec![existing], shift(1, at(10, 18), 4), Action::Book);
+
+        assert!(check(&request).is_some());
+    }
+
+    #[test]
+    fn test_non_conflicting_shifts_pass() {
+        let mut other_position = shift(2, at(10, 12), 8);
+        other_position.position_type_id = 1;
+        other_position.match_status_id = 1;
+
+        let mut below_match_status = shift(3, at(10, 12), 8);
+        below_match_status.position_type_id = 2;
+        below_match_status.match_status_id = 0;
+
+        let mut next_day = shift(4, at(11, 12), 8);
+        next_day.position_type_id = 2;
+        next_day.match_status_id = 1;
+
+        let request = request(
+            vec![other_position, below_match_status, next_day],
+            shift(1, at(10, 16), 4),
+            Action::Book,
+        );
+
+        assert_eq!(check(&request), None);
+    }
+
+    #[test]
+    fn test_cancel_is_not_checked() {
+        let mut existing = shift(2, at(10, 12), 8);
+        existing.position_type_id = 2;
+        existing.match_status_id = 1;
+        let request = request(vec![existing], shift(1, at(10, 16), 8), Action::Cancel);
+
+        assert_eq!(check(&request), None);
+    }
+}
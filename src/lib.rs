@@ -0,0 +1,4 @@
+pub mod config;
+pub mod handlers;
+pub mod library;
+pub mod simulation;
@@ -0,0 +1,147 @@
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of "now" for `RuleValidationService::evaluate_now` and of the config watcher's timers. </br>
+/// Rules only ever look at `ValidationRequest::requested_at`, the clock is what stamps it.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Current local time.
+    fn now(&self) -> NaiveDateTime;
+
+    /// Real time that passes while `duration` passes on this clock, what timers actually wait.
+    fn real_duration(&self, duration: Duration) -> Duration {
+        duration
+    }
+
+    /// Moves a manually driven clock forward to `at`, never back. Clocks running on their own ignore it.
+    fn advance_to(&self, _at: NaiveDateTime) {}
+}
+
+/// Clock shared between the services that should agree on the time.
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Wall clock, local time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+}
+
+/// Pinned to an instant that only moves when told to, for tests.
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<NaiveDateTime>,
+}
+
+impl FixedClock {
+    pub fn new(now: NaiveDateTime) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    pub fn set(&self, now: NaiveDateTime) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, delta: TimeDelta) {
+        *self.now.lock().unwrap() += delta;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> NaiveDateTime {
+        *self.now.lock().unwrap()
+    }
+
+    fn advance_to(&self, at: NaiveDateTime) {
+        let mut now = self.now.lock().unwrap();
+        *now = at.max(*now);
+    }
+}
+
+/// Runs `factor` times faster than real time from `origin` on, e.g. a factor of `86_400 / 10`
+/// simulates a day in 10 seconds. Timers shrink by the same factor.
+#[derive(Debug, Clone)]
+pub struct AcceleratedClock {
+    origin: NaiveDateTime,
+    started: Instant,
+    factor: u32,
+}
+
+impl AcceleratedClock {
+    /// A `factor` of 0 is treated as 1.
+    pub fn new(origin: NaiveDateTime, factor: u32) -> Self {
+        Self {
+            origin,
+            started: Instant::now(),
+            factor: factor.max(1),
+        }
+    }
+
+    pub fn factor(&self) -> u32 {
+        self.factor
+    }
+}
+
+impl Clock for AcceleratedClock {
+    fn now(&self) -> NaiveDateTime {
+        let elapsed = self.started.elapsed() * self.factor;
+        self.origin + TimeDelta::from_std(elapsed).unwrap_or(TimeDelta::MAX)
+    }
+
+    fn real_duration(&self, duration: Duration) -> Duration {
+        duration / self.factor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 2, 10)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_fixed_clock_only_moves_when_told() {
+        let clock = FixedClock::new(at(9));
+        assert_eq!(clock.now(), at(9));
+        assert_eq!(clock.now(), at(9));
+
+        clock.advance(TimeDelta::hours(2));
+        assert_eq!(clock.now(), at(11));
+        clock.set(at(6));
+        assert_eq!(clock.now(), at(6));
+        clock.advance_to(at(8));
+        clock.advance_to(at(7));
+        assert_eq!(clock.now(), at(8));
+        assert_eq!(
+            clock.real_duration(Duration::from_secs(1)),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn test_accelerated_clock_scales_time() {
+        let clock = AcceleratedClock::new(at(0), 3600);
+        assert_eq!(
+            clock.real_duration(Duration::from_secs(3600)),
+            Duration::from_secs(1)
+        );
+
+        std::thread::sleep(Duration::from_millis(10));
+        // 10ms of real time are (at least) 36s on the clock
+        assert!(clock.now() >= at(0) + TimeDelta::seconds(36));
+        assert!(clock.now() < at(1));
+    }
+}
@@ -1,41 +1,152 @@
+use crate::config::lint::{self, ConfigLint};
+use crate::config::overlay;
 pub use crate::config::prelude::*;
+use crate::library::clock::SharedClock;
+use crate::library::file_watcher::{FileWatcher, WatchOptions};
+use crate::library::reload_state::ReloadState;
 use crate::library::{Error, Result};
 
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-use quick_xml::{de::from_str, events::Event, Reader};
-use tokio::{fs::File, io::AsyncReadExt, sync::watch};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use tokio::sync::watch;
 
-#[cfg(windows)]
-const LINE_ENDING: &'static str = "\r\n";
-#[cfg(not(windows))]
-const LINE_ENDING: &'static str = "\n";
+/// Payload of the config channel: an accepted config together with its identity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigSnapshot {
+    /// Starts at 1, incremented with every published config
+    pub revision: u64,
+    /// sha256 (hex) of the file contents the config was parsed from (base file followed by the overlays)
+    pub content_hash: String,
+    pub loaded_at: DateTime<Utc>,
+    /// Base config file
+    pub source: PathBuf,
+    pub config: Config,
+    /// Which layer every effective value comes from
+    pub provenance: Provenance,
+    /// What changed compared to the previous revision
+    pub diff: ConfigDiff,
+}
+
+impl ConfigSnapshot {
+    /// First revision of a config that is not served by a `ConfigurationService` (nothing is watched),
+    /// the content hash is taken over its XML form.
+    pub fn new(source: PathBuf, config: Config) -> Result<Self> {
+        Ok(Self {
+            revision: 1,
+            content_hash: content_hash(&config.to_xml()?),
+            loaded_at: Utc::now(),
+            provenance: Provenance::new(source.clone()),
+            source,
+            config,
+            diff: ConfigDiff::default(),
+        })
+    }
+}
 
 #[derive(Debug)]
 pub struct ConfigurationService {
     config_path: PathBuf,
-    tx: watch::Sender<Config>,
-    rx: watch::Receiver<Config>,
+    /// Format of `config_path`, by file extension
+    format: ConfigFormat,
+    /// Applied in order on top of `config_path`
+    overlay_paths: Vec<PathBuf>,
+    watch_options: WatchOptions,
+    tx: watch::Sender<ConfigSnapshot>,
+    rx: watch::Receiver<ConfigSnapshot>,
+    state_tx: watch::Sender<ReloadState>,
+    /// Contents of the last quarantined file, so a file that keeps failing is only copied once
+    last_quarantined: Mutex<Option<String>>,
+    /// Last `WatchOptions::history_len` accepted revisions, oldest first
+    history: Mutex<VecDeque<HistoryEntry>>,
+    /// Serializes `reload` / `update` / `rollback_to` / `edit_file`, so concurrent writers don't overwrite
+    /// each other's changes and history entries are recorded in revision order
+    write_lock: Mutex<()>,
+}
+
+/// Accepted revision together with the exact base file contents it was loaded from.
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    snapshot: ConfigSnapshot,
+    contents: String,
+}
+
+/// Base config with the overlays applied, ready to be published.
+#[derive(Debug, Clone)]
+struct Layered {
+    config: Config,
+    provenance: Provenance,
+    base_contents: String,
+    content_hash: String,
 }
 
 impl ConfigurationService {
     ///Spawns a task that watches for changes in the configuration file </br>
     /// NOTE: Be careful to validate that ValidationRule handlers actually run in the separate thasks!
     pub async fn new(config_path: String) -> Arc<Self> {
-        let initial_config = Self::read_config(&config_path)
+        Self::with_options(config_path, WatchOptions::default()).await
+    }
+
+    pub async fn with_options(config_path: String, watch_options: WatchOptions) -> Arc<Self> {
+        Self::with_overlays(config_path, Vec::new(), watch_options).await
+    }
+
+    /// Serves `config_path` with the `overlay_paths` files applied on top of it, in order (see `Overlay`). </br>
+    /// A change in any of the files reloads all of them. The base config can be in any `ConfigFormat`,
+    /// overlays are always XML.
+    pub async fn with_overlays(
+        config_path: String,
+        overlay_paths: Vec<String>,
+        watch_options: WatchOptions,
+    ) -> Arc<Self> {
+        Self::try_with_overlays(config_path, overlay_paths, watch_options)
             .await
             .map_err(|e| format!("Initial config failed to load. / {e}"))
-            .unwrap();
+            .unwrap()
+    }
 
-        let (tx, rx) = watch::channel(initial_config);
-        //TODO : 'tx' --> In the future i might have separate task worker that updates xml file at random (to simulate "simulation engine" that updates the XML file)
+    /// Like `with_overlays`, but returns the error of the initial load (or of `watch_options`)
+    /// instead of panicking.
+    pub async fn try_with_overlays(
+        config_path: String,
+        overlay_paths: Vec<String>,
+        watch_options: WatchOptions,
+    ) -> Result<Arc<Self>> {
+        if watch_options.history_len == 0 {
+            return Err(Error::InvalidWatchOptions(
+                "history_len must be at least 1, `update` edits the latest retained revision"
+                    .to_string(),
+            ));
+        }
+        let config_path = PathBuf::from(config_path);
+        let overlay_paths: Vec<PathBuf> = overlay_paths.into_iter().map(PathBuf::from).collect();
+        let format = ConfigFormat::from_path(&config_path)?;
+        let contents = Self::read_source(&config_path)?;
+        let base = format.parse(&contents)?;
+        let layered = Self::layer(&config_path, &overlay_paths, base, contents)?;
+        Self::report_lints(&lint::lint(&layered.config));
 
-        let service = Arc::new(Self {
-            config_path: PathBuf::from(config_path),
-            tx,
-            rx,
-        });
+        let snapshot = ConfigSnapshot {
+            revision: 1,
+            content_hash: layered.content_hash,
+            loaded_at: Utc::now(),
+            source: config_path.clone(),
+            config: layered.config,
+            provenance: layered.provenance,
+            diff: ConfigDiff::default(),
+        };
+
+        let service = Arc::new(Self::from_snapshot(
+            config_path,
+            format,
+            overlay_paths,
+            watch_options,
+            snapshot,
+            layered.base_contents,
+        ));
 
         let service_clone = Arc::clone(&service);
 
@@ -45,25 +156,182 @@ impl ConfigurationService {
             service_clone.watch_config_changes().await;
         });
 
+        Ok(service)
+    }
+
+    fn from_snapshot(
+        config_path: PathBuf,
+        format: ConfigFormat,
+        overlay_paths: Vec<PathBuf>,
+        watch_options: WatchOptions,
+        snapshot: ConfigSnapshot,
+        contents: String,
+    ) -> Self {
+        let (tx, rx) = watch::channel(snapshot.clone());
+        let service = Self {
+            config_path,
+            format,
+            overlay_paths,
+            watch_options,
+            tx,
+            rx,
+            state_tx: watch::Sender::new(ReloadState::Loaded),
+            last_quarantined: Mutex::new(None),
+            history: Mutex::new(VecDeque::new()),
+            write_lock: Mutex::new(()),
+        };
+        service.record_history(snapshot, contents);
         service
     }
 
-    pub fn subscribe(&self) -> watch::Receiver<Config> {
+    /// `WatchOptions::clock`, validations against this config use it too.
+    pub fn clock(&self) -> &SharedClock {
+        &self.watch_options.clock
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<ConfigSnapshot> {
         self.rx.clone()
     }
 
-    /// reads config once.
-    async fn read_config(path: &str) -> Result<Config> {
+    pub fn subscribe_state(&self) -> watch::Receiver<ReloadState> {
+        self.state_tx.subscribe()
+    }
+
+    /// Why the current file contents were rejected, `None` when the served config matches the file.
+    pub fn pending_error(&self) -> Option<String> {
+        self.state_tx.borrow().pending_error().map(str::to_string)
+    }
+
+    /// Retained revisions, oldest first.
+    pub fn history(&self) -> Vec<ConfigSnapshot> {
+        let history = self.history.lock().unwrap();
+        history.iter().map(|entry| entry.snapshot.clone()).collect()
+    }
+
+    /// Restores the base file contents of an earlier `revision`: the file is rewritten atomically and,
+    /// with the current overlays applied, published right away as a new revision (revisions never go back).
+    pub fn rollback_to(&self, revision: u64) -> Result<ConfigSnapshot> {
+        let _write_guard = self.write_lock.lock().unwrap();
+        let entry = {
+            let history = self.history.lock().unwrap();
+            history
+                .iter()
+                .find(|entry| entry.snapshot.revision == revision)
+                .cloned()
+                .ok_or(Error::UnknownRevision(revision))?
+        };
+
+        let base = self.format.parse(&entry.contents)?;
+        let layered = Self::layer(&self.config_path, &self.overlay_paths, base, entry.contents)?;
+
+        write_atomically(&self.config_path, &layered.base_contents)?;
+        // The watcher reloads the rewritten file too, but only the first of both publishes
+        let diff = self.publish(layered);
+        if let Some(diff) = &diff {
+            println!("--> Config rolled back to revision {revision}:\n{diff}");
+        }
+        self.set_state(|state| state.succeeded(diff.is_some(), Utc::now()));
+
+        Ok(self.rx.borrow().clone())
+    }
+
+    /// Applies `mutate` to the base config of the served revision, then validates it, rewrites the base
+    /// file atomically and publishes the result (with the overlays applied on top). </br>
+    /// Nothing is written when the mutation changes nothing, or when it is rejected: the written file
+    /// must parse (and pass `validator` for XML) and the result must not have lints the served config
+    /// doesn't have (`Error::RejectedUpdate`). </br>
+    /// The file is rewritten from the parsed config: XML root attributes (e.g. the schema reference) are
+    /// kept, comments and formatting are not.
+    pub fn update(&self, mutate: impl FnOnce(&mut Config)) -> Result<ConfigSnapshot> {
+        let _write_guard = self.write_lock.lock().unwrap();
+        let base_contents = {
+            let history = self.history.lock().unwrap();
+            history
+                .back()
+                .map(|entry| entry.contents.clone())
+                .unwrap_or_default()
+        };
+        let current = self.format.parse(&base_contents)?;
+        let mut base = current.clone();
+        mutate(&mut base);
+        if base == current {
+            return Ok(self.rx.borrow().clone());
+        }
+
+        let contents = self.format.write(&base)?;
+        let base = self.format.parse(&contents)?;
+        let layered = Self::layer(&self.config_path, &self.overlay_paths, base, contents)?;
+        let lints = lint::lint(&layered.config);
+        let served_lints = lint::lint(&self.rx.borrow().config);
+        let introduced: Vec<ConfigLint> = lints
+            .iter()
+            .filter(|lint| !served_lints.contains(lint))
+            .cloned()
+            .collect();
+        if !introduced.is_empty() {
+            return Err(Error::RejectedUpdate(introduced));
+        }
+
+        write_atomically(&self.config_path, &layered.base_contents)?;
+        let diff = self.publish(layered);
+        if let Some(diff) = &diff {
+            println!("--> Config updated:\n{diff}");
+            Self::report_lints(&lints);
+        }
+        self.set_state(|state| state.succeeded(diff.is_some(), Utc::now()));
+
+        Ok(self.rx.borrow().clone())
+    }
+
+    /// Applies `mutate` to the base file and rewrites it atomically without publishing anything: the
+    /// watcher reloads it like an external edit (see `simulation::chaos`). </br>
+    /// Returns the config that reload is expected to publish, with the overlays applied, or the error
+    /// of `mutate`, in which case nothing is written. Drops comments like `update`.
+    pub fn edit_file<E>(
+        &self,
+        mutate: impl FnOnce(&mut Config) -> std::result::Result<(), E>,
+    ) -> Result<std::result::Result<Config, E>> {
+        let _write_guard = self.write_lock.lock().unwrap();
+        let mut base = self.format.parse(&Self::read_source(&self.config_path)?)?;
+        if let Err(e) = mutate(&mut base) {
+            return Ok(Err(e));
+        }
+
+        let contents = self.format.write(&base)?;
+        let base = self.format.parse(&contents)?;
+        let layered = Self::layer(&self.config_path, &self.overlay_paths, base, contents)?;
+
+        write_atomically(&self.config_path, &layered.base_contents)?;
+        Ok(Ok(layered.config))
+    }
+
+    fn read_source(path: &Path) -> Result<String> {
         println!("Reader -- reading config ... ");
-        let xml = std::fs::read_to_string(path)?;
-        let mut reader = Reader::from_str(&xml);
+        Ok(std::fs::read_to_string(path)?)
+    }
 
-        // loop {
-        //    //TODO: config.rs test suite code goes here
-        // }
+    /// Reads the overlays and applies them on top of the already parsed base config.
+    fn layer(
+        config_path: &Path,
+        overlay_paths: &[PathBuf],
+        base: Config,
+        base_contents: String,
+    ) -> Result<Layered> {
+        let mut contents = base_contents.clone();
+        let mut overlays = Vec::with_capacity(overlay_paths.len());
+        for path in overlay_paths {
+            let xml = Self::read_source(path)?;
+            overlays.push(Overlay::parse(path.clone(), &xml)?);
+            contents.push_str(&xml);
+        }
+        let (config, provenance) = overlay::merge(&base, config_path, &overlays)?;
 
-        let _config: Config = from_str(&xml)?;
-        Ok(_config)
+        Ok(Layered {
+            config,
+            provenance,
+            base_contents,
+            content_hash: content_hash(&contents),
+        })
     }
 
     // **example** of attribute parsing
@@ -91,21 +359,233 @@ impl ConfigurationService {
     //     Ok(format!("{}({})", action.name, args.join(", ")))
     // }
 
-    /// continuously reads config
+    /// Reloads the config on file system notifications (debounced), polls when notifications are unavailable.
     async fn watch_config_changes(&self) {
-        let mut one_sec = tokio::time::interval(std::time::Duration::from_secs(1));
+        // Error is not `Send`, so it can't be held across the awaits below
+        let mut files = vec![self.config_path.clone()];
+        files.extend(self.overlay_paths.iter().cloned());
+
+        match FileWatcher::for_files(&files).map_err(|e| e.to_string()) {
+            Ok(mut watcher) => {
+                // Catch up with edits made between the initial load and the watcher registration
+                self.reload().await;
+                let debounce = self
+                    .watch_options
+                    .real_duration(self.watch_options.debounce);
+                while watcher.changed(debounce).await.is_some() {
+                    self.reload().await;
+                }
+                eprintln!("--> File notifications stopped, falling back to polling");
+            }
+            Err(e) => {
+                eprintln!("--> File notifications unavailable ({e}), falling back to polling")
+            }
+        }
+
+        self.poll_config_changes().await;
+    }
+
+    /// continuously reads config
+    async fn poll_config_changes(&self) {
+        let mut interval = tokio::time::interval(
+            self.watch_options
+                .real_duration(self.watch_options.poll_interval),
+        );
 
         loop {
-            one_sec.tick().await;
+            interval.tick().await;
+            self.reload().await;
+        }
+    }
 
-            match Self::read_config(self.config_path.to_str().unwrap()).await {
-                Ok(_new_cfg) => {}
-                Err(e) => eprintln!(
-                    "--> Config ERROR: {e}" //TODO: At this point we exited the Reader buffer and should have
-                ),
+    /// Only a config that differs from the current one is published, on read/parse errors subscribers keep the last good config.
+    async fn reload(&self) {
+        // Read and publish as one step, so an `update` in between is not overwritten by older file contents
+        let _write_guard = self.write_lock.lock().unwrap();
+        let xml = match Self::read_source(&self.config_path) {
+            Ok(xml) => xml,
+            Err(e) => return self.reload_failed(e),
+        };
+        let base = match self.format.parse(&xml) {
+            Ok(base) => base,
+            Err(e) => {
+                self.quarantine(&xml);
+                return self.reload_failed(e);
             }
+        };
+
+        match Self::layer(&self.config_path, &self.overlay_paths, base, xml) {
+            Ok(layered) => {
+                let lints = lint::lint(&layered.config);
+                let diff = self.publish(layered);
+                if let Some(diff) = &diff {
+                    println!("--> Config change published:\n{diff}");
+                    Self::report_lints(&lints);
+                }
+                self.set_state(|state| state.succeeded(diff.is_some(), Utc::now()));
+            }
+            Err(e) => self.reload_failed(e),
+        }
+    }
+
+    fn reload_failed(&self, e: Error) {
+        eprintln!("--> Config ERROR: {e}");
+        self.set_state(|state| state.failed(e.to_string(), Utc::now()));
+    }
+
+    fn set_state(&self, transition: impl FnOnce(&ReloadState) -> ReloadState) {
+        self.state_tx.send_if_modified(|state| {
+            let next = transition(state);
+            if next == *state {
+                return false;
+            }
+            *state = next;
+            true
+        });
+    }
+
+    /// Copies rejected base file contents into `WatchOptions::quarantine_dir` (if configured).
+    fn quarantine(&self, xml: &str) {
+        let Some(dir) = &self.watch_options.quarantine_dir else {
+            return;
+        };
+        let mut last_quarantined = self.last_quarantined.lock().unwrap();
+        if last_quarantined.as_deref() == Some(xml) {
+            return;
+        }
+
+        let stem = self
+            .config_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let path = dir.join(format!(
+            "{stem}.{}.rejected.{}",
+            Utc::now().format("%Y%m%dT%H%M%S%.3f"),
+            self.format.extension()
+        ));
+
+        match std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, xml)) {
+            Ok(()) => {
+                eprintln!("--> Rejected config quarantined at {}", path.display());
+                *last_quarantined = Some(xml.to_string());
+            }
+            Err(e) => eprintln!("--> Failed to quarantine rejected config: {e}"),
         }
     }
+
+    /// Semantic problems don't block loading the config, they are only reported.
+    fn report_lints(lints: &[ConfigLint]) {
+        for lint in lints {
+            eprintln!("--> Config WARNING: {lint}");
+        }
+    }
+
+    /// Keeps the accepted revision in memory and, if `WatchOptions::history_dir` is set, on disk.
+    fn record_history(&self, snapshot: ConfigSnapshot, contents: String) {
+        if let Some(dir) = &self.watch_options.history_dir {
+            if let Err(e) = self.write_history_file(dir, &snapshot, &contents) {
+                eprintln!("--> Failed to write config history: {e}");
+            }
+        }
+
+        let mut history = self.history.lock().unwrap();
+        history.push_back(HistoryEntry { snapshot, contents });
+        while history.len() > self.watch_options.history_len {
+            history.pop_front();
+        }
+    }
+
+    /// Files are named `<stem>.<timestamp>.r<revision>.<extension>`, so they sort oldest first.
+    fn write_history_file(
+        &self,
+        dir: &Path,
+        snapshot: &ConfigSnapshot,
+        contents: &str,
+    ) -> std::io::Result<()> {
+        let extension = self.format.extension();
+        let stem = self
+            .config_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(
+            dir.join(format!(
+                "{stem}.{}.r{}.{extension}",
+                snapshot.loaded_at.format("%Y%m%dT%H%M%S%.3f"),
+                snapshot.revision
+            )),
+            contents,
+        )?;
+
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                name.starts_with(&format!("{stem}."))
+                    && name
+                        .strip_suffix(&format!(".{extension}"))
+                        .and_then(|name| name.rsplit_once(".r"))
+                        .is_some_and(|(_, revision)| revision.parse::<u64>().is_ok())
+            })
+            .collect();
+        files.sort();
+        let excess = files.len().saturating_sub(self.watch_options.history_len);
+        for path in &files[..excess] {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the diff when the layered config differed from the current config and subscribers were
+    /// notified of the next revision.
+    fn publish(&self, layered: Layered) -> Option<ConfigDiff> {
+        let Layered {
+            config: new_cfg,
+            provenance,
+            base_contents,
+            content_hash,
+        } = layered;
+        let mut published = None;
+        self.tx.send_if_modified(|current| {
+            if current.config == new_cfg {
+                return false;
+            }
+            let diff = ConfigDiff::between(&current.config, &new_cfg);
+            *current = ConfigSnapshot {
+                revision: current.revision + 1,
+                content_hash,
+                loaded_at: Utc::now(),
+                source: self.config_path.clone(),
+                config: new_cfg,
+                provenance,
+                diff,
+            };
+            // The snapshot that was sent, not a later one another writer may have published meanwhile
+            published = Some(current.clone());
+            true
+        });
+        let snapshot = published?;
+        let diff = snapshot.diff.clone();
+        self.record_history(snapshot, base_contents);
+        Some(diff)
+    }
+}
+
+/// Writes a temp file next to `path` and renames it over `path`, readers never see a partial file.
+fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!(".{file_name}.tmp"));
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+fn content_hash(xml: &str) -> String {
+    Sha256::digest(xml.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
 }
 
 #[cfg(test)]
@@ -113,6 +593,7 @@ mod tests {
     use super::*;
     use crate::library::RuleType;
     use pretty_assertions::assert_eq;
+    use quick_xml::de::from_str;
 
     #[tokio::test]
     async fn test_rule_validation_settings_parsing() {
@@ -120,7 +601,7 @@ mod tests {
 
         // Get config via subscription
         let receiver = service.subscribe();
-        let config = receiver.borrow().clone();
+        let config = receiver.borrow().config.clone();
 
         dbg!(&config);
 
@@ -143,24 +624,605 @@ mod tests {
             Some(0)
         );
     }
-}
 
-// TODO: Test config file update
+    /// Copy of `test_config.xml` the test is free to rewrite.
+    fn scratch_config(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}_{}.xml", name, std::process::id()));
+        std::fs::copy("test_config.xml", &path).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_config_file_update_is_published() {
+        let path = scratch_config("test_config_file_update");
+        let service = ConfigurationService::new(path.clone()).await;
+        let mut receiver = service.subscribe();
+        let initial = receiver.borrow().clone();
+        assert_eq!(initial.revision, 1);
+        assert_eq!(initial.source, PathBuf::from(&path));
+
+        let updated = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace(r#"PositionTypeIDs="1""#, r#"PositionTypeIDs="1,2""#);
+        std::fs::write(&path, updated).unwrap();
+
+        // Wait for watcher to detect change
+        tokio::time::timeout(std::time::Duration::from_secs(3), receiver.changed())
+            .await
+            .expect("config change was not published")
+            .unwrap();
+
+        let snapshot = receiver.borrow_and_update().clone();
+        assert_eq!(
+            snapshot.diff.to_string(),
+            "~ Shift/SideJobPrevention PositionTypeIDs: \"1\" -> \"1,2\""
+        );
+        assert_eq!(snapshot.revision, 2);
+        assert_eq!(snapshot.source, initial.source);
+        assert!(snapshot.loaded_at > initial.loaded_at);
+        assert_eq!(
+            snapshot.content_hash,
+            content_hash(&std::fs::read_to_string(&path).unwrap())
+        );
+        let updated_config = snapshot.config;
+        assert_eq!(
+            updated_config
+                .validation_rules
+                .groups
+                .validation_rules_groups[0]
+                .validation_rules[0]
+                .position_type_ids,
+            vec![1, 2]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_revision_only_increases_on_published_reloads() {
+        let path = scratch_config("test_revisions");
+        let service = ConfigurationService::with_options(
+            path.clone(),
+            WatchOptions {
+                debounce: std::time::Duration::from_millis(50),
+                ..Default::default()
+            },
+        )
+        .await;
+        let mut receiver = service.subscribe();
+        let original = std::fs::read_to_string(&path).unwrap();
+
+        let mut revisions = vec![receiver.borrow().revision];
+        for ids in ["1,2", "1,2,3", "1"] {
+            let updated = original.replace(
+                r#"PositionTypeIDs="1""#,
+                &format!(r#"PositionTypeIDs="{ids}""#),
+            );
+            std::fs::write(&path, updated).unwrap();
+            tokio::time::timeout(std::time::Duration::from_secs(3), receiver.changed())
+                .await
+                .expect("config change was not published")
+                .unwrap();
+            revisions.push(receiver.borrow_and_update().revision);
+        }
+        assert_eq!(revisions, vec![1, 2, 3, 4]);
+
+        // Same config, different file contents: reloaded but not published
+        std::fs::write(&path, original.replace("Test Rules Config", "Same Rules")).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert_eq!(receiver.has_changed().unwrap(), false);
+        assert_eq!(receiver.borrow().revision, 4);
+        assert_eq!(service.history().len(), 4);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_standalone_snapshot_hashes_its_xml() {
+        let config: Config =
+            from_str(&std::fs::read_to_string("test_config.xml").unwrap()).unwrap();
 
-// let updated_content = r#"<?xml version="1.0" encoding="UTF-8"?>
-//     <Configuration>
-//         <rules>
-//             <RuleType>ValidationRule</RuleType>
-//         </rules>
-//     </Configuration>"#;
+        let snapshot =
+            ConfigSnapshot::new(PathBuf::from("test_config.xml"), config.clone()).unwrap();
 
-// // fs::write(&tmp_file, updated_content).unwrap();
+        assert_eq!(snapshot.revision, 1);
+        assert_eq!(snapshot.source, PathBuf::from("test_config.xml"));
+        assert_eq!(
+            snapshot.content_hash,
+            content_hash(&config.to_xml().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_atomic_replace_is_published() {
+        let path = scratch_config("test_atomic_replace");
+        let service = ConfigurationService::new(path.clone()).await;
+        let mut receiver = service.subscribe();
+
+        // Editors commonly write a temp file and rename it over the original
+        let tmp_path = format!("{path}.tmp");
+        let updated = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace(r#"Enabled="True""#, r#"Enabled="False""#);
+        std::fs::write(&tmp_path, updated).unwrap();
+        std::fs::rename(&tmp_path, &path).unwrap();
+
+        tokio::time::timeout(std::time::Duration::from_secs(3), receiver.changed())
+            .await
+            .expect("config change was not published")
+            .unwrap();
+
+        assert_eq!(
+            receiver
+                .borrow_and_update()
+                .config
+                .validation_rules
+                .groups
+                .validation_rules_groups[0]
+                .validation_rules[0]
+                .enabled,
+            false
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_broken_config_keeps_last_good() {
+        let path = scratch_config("test_broken_config");
+        let good_xml = std::fs::read_to_string(&path).unwrap();
+        let quarantine_dir =
+            std::env::temp_dir().join(format!("test_quarantine_{}", std::process::id()));
+        let options = WatchOptions {
+            quarantine_dir: Some(quarantine_dir.clone()),
+            ..WatchOptions::default()
+        };
+        let service = ConfigurationService::with_options(path.clone(), options).await;
+        let mut receiver = service.subscribe();
+        let mut state = service.subscribe_state();
+        let last_good = receiver.borrow().clone();
+
+        std::fs::write(&path, "<config><ValidationRules>").unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(3), state.changed())
+            .await
+            .expect("reload failure was not reported")
+            .unwrap();
+
+        assert!(matches!(
+            *state.borrow_and_update(),
+            ReloadState::ReloadFailed { .. }
+        ));
+        assert!(service.pending_error().is_some());
+        assert_eq!(receiver.has_changed().unwrap(), false);
+        assert_eq!(*receiver.borrow_and_update(), last_good);
+
+        std::fs::write(&path, good_xml).unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(3), state.changed())
+            .await
+            .expect("recovery was not reported")
+            .unwrap();
+
+        assert!(matches!(
+            *state.borrow_and_update(),
+            ReloadState::Recovered { .. }
+        ));
+        assert_eq!(service.pending_error(), None);
+        assert_eq!(std::fs::read_dir(&quarantine_dir).unwrap().count(), 1);
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_dir_all(quarantine_dir).unwrap();
+    }
+
+    fn snapshot(config: Config) -> ConfigSnapshot {
+        ConfigSnapshot {
+            revision: 1,
+            content_hash: String::new(),
+            loaded_at: Utc::now(),
+            source: PathBuf::from("test_config.xml"),
+            config,
+            provenance: Provenance::new(PathBuf::from("test_config.xml")),
+            diff: ConfigDiff::default(),
+        }
+    }
+
+    fn layered(config: Config, xml: &str) -> Layered {
+        Layered {
+            config,
+            provenance: Provenance::new(PathBuf::from("test_config.xml")),
+            base_contents: xml.to_string(),
+            content_hash: content_hash(xml),
+        }
+    }
+
+    #[test]
+    fn test_unchanged_config_is_not_published() {
+        let config: Config =
+            from_str(&std::fs::read_to_string("test_config.xml").unwrap()).unwrap();
+        let service = ConfigurationService::from_snapshot(
+            PathBuf::from("test_config.xml"),
+            ConfigFormat::Xml,
+            Vec::new(),
+            WatchOptions::default(),
+            snapshot(config.clone()),
+            String::new(),
+        );
+
+        assert_eq!(service.publish(layered(config.clone(), "")), None);
+
+        let mut changed = config;
+        changed.validation_rules.groups.validation_rules_groups[0].validation_rules[0].enabled =
+            false;
+        assert_eq!(
+            service
+                .publish(layered(changed, "changed"))
+                .unwrap()
+                .to_string(),
+            "~ Shift/SideJobPrevention Enabled: \"True\" -> \"False\""
+        );
+        assert_eq!(service.subscribe().borrow().revision, 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_writes_and_publishes() {
+        let path = scratch_config("test_update");
+        let service = ConfigurationService::new(path.clone()).await;
+        let mut receiver = service.subscribe();
+
+        let updated = service
+            .update(|config| {
+                config.validation_rules.groups.validation_rules_groups[0].validation_rules[0]
+                    .enabled = false;
+            })
+            .unwrap();
+
+        assert_eq!(updated.revision, 2);
+        assert_eq!(
+            updated.diff.to_string(),
+            "~ Shift/SideJobPrevention Enabled: \"True\" -> \"False\""
+        );
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(updated.content_hash, content_hash(&written));
+        assert_eq!(
+            from_str::<Config>(&written).unwrap(),
+            receiver.borrow_and_update().config
+        );
+
+        // A mutation that doesn't parse back leaves file and subscribers untouched
+        let rejected = service.update(|config| {
+            config
+                .validation_rules
+                .groups
+                .validation_rules_groups
+                .clear();
+        });
+        assert!(matches!(rejected, Err(Error::Serde(_))));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+        assert_eq!(receiver.has_changed().unwrap(), false);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_polling_follows_an_accelerated_clock() {
+        let path = scratch_config("test_accelerated_poll");
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let config = ConfigFormat::Xml.parse(&contents).unwrap();
+        // An hour of poll interval is 100ms of real time
+        let origin = chrono::NaiveDate::from_ymd_opt(2025, 2, 10)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let watch_options = WatchOptions {
+            poll_interval: std::time::Duration::from_secs(3600),
+            clock: Arc::new(crate::library::clock::AcceleratedClock::new(origin, 36_000)),
+            ..Default::default()
+        };
+        // Without the file watcher, only polling picks the change up
+        let service = Arc::new(ConfigurationService::from_snapshot(
+            PathBuf::from(&path),
+            ConfigFormat::Xml,
+            Vec::new(),
+            watch_options,
+            ConfigSnapshot::new(PathBuf::from(&path), config).unwrap(),
+            contents.clone(),
+        ));
+        let mut receiver = service.subscribe();
+        let poller = Arc::clone(&service);
+        tokio::spawn(async move { poller.poll_config_changes().await });
+        // Past the first poll, which happens right away
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        std::fs::write(
+            &path,
+            contents.replace(r#"PositionTypeIDs="1""#, r#"PositionTypeIDs="1,2""#),
+        )
+        .unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(2), receiver.changed())
+            .await
+            .expect("poll interval was not shortened by the clock")
+            .unwrap();
+        assert_eq!(receiver.borrow_and_update().revision, 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_is_checked_before_writing() {
+        let path = scratch_config("test_update_checked");
+        let original = std::fs::read_to_string(&path).unwrap();
+        let service = ConfigurationService::new(path.clone()).await;
+        let mut receiver = service.subscribe();
 
-// // Wait for watcher to detect change
-// tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        // Nothing changed, the file (and its comments) stay as they are
+        let unchanged = service.update(|_| {}).unwrap();
+        assert_eq!(unchanged.revision, 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
 
-// let updated_config = receiver.borrow().clone();
-// assert_eq!(updated_config.rules.len(), 1);
-// assert!(matches!(updated_config.rules[0], RuleType::ValidationRule));
+        // Rule[1] would overlap Rule[0]
+        let rejected = service.update(|config| {
+            config.validation_rules.groups.validation_rules_groups[0].validation_rules[0]
+                .rules
+                .rules[1]
+                .set_attribute("ForCandidateStatusIds", "1")
+                .unwrap();
+        });
+        let Err(Error::RejectedUpdate(lints)) = rejected else {
+            panic!("update was accepted: {rejected:?}");
+        };
+        assert_eq!(
+            lints.iter().map(|l| l.to_string()).collect::<Vec<_>>(),
+            vec!["Shift/SideJobPrevention: Rule[0] and Rule[1] overlap on ForCandidateStatusIds 1"]
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+        assert_eq!(receiver.has_changed().unwrap(), false);
+        assert_eq!(receiver.borrow_and_update().revision, 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_schema_reference() {
+        let path =
+            std::env::temp_dir().join(format!("test_update_schema_{}.xml", std::process::id()));
+        std::fs::copy("validator_config.xml", &path).unwrap();
+        let path = path.to_str().unwrap().to_string();
+        let service = ConfigurationService::new(path.clone()).await;
+
+        service
+            .update(|config| {
+                config.validation_rules.groups.validation_rules_groups[0].validation_rules[0]
+                    .enabled = false;
+            })
+            .unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with(
+            r#"<config xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="schema/validator_config.xsd">"#
+        ));
+        let reparsed = ConfigFormat::Xml.parse(&written).unwrap();
+        assert_eq!(reparsed, service.subscribe().borrow().config);
+        assert_eq!(
+            reparsed.root_attributes,
+            service.subscribe().borrow().config.root_attributes
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_history_matches_revisions_under_concurrent_writes() {
+        let path = scratch_config("test_concurrent_history");
+        let service = ConfigurationService::with_options(
+            path.clone(),
+            WatchOptions {
+                debounce: std::time::Duration::from_millis(5),
+                history_len: 100,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        // Two writers racing each other and the watcher reloading their writes
+        let writers: Vec<_> = (0..2)
+            .map(|writer| {
+                let service = Arc::clone(&service);
+                std::thread::spawn(move || {
+                    for i in 0..10 {
+                        service
+                            .update(|config| {
+                                config.validation_rules.groups.validation_rules_groups[0]
+                                    .validation_rules[0]
+                                    .position_type_ids = vec![writer, i];
+                            })
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let history = service.history.lock().unwrap().clone();
+        let revisions: Vec<u64> = history
+            .iter()
+            .map(|entry| entry.snapshot.revision)
+            .collect();
+        assert_eq!(revisions, (1..=history.len() as u64).collect::<Vec<_>>());
+        for entry in &history {
+            assert_eq!(
+                from_str::<Config>(&entry.contents).unwrap(),
+                entry.snapshot.config,
+                "revision {} recorded with the contents of another revision",
+                entry.snapshot.revision
+            );
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "history_len must be at least 1")]
+    async fn test_empty_history_is_rejected() {
+        let _ = ConfigurationService::with_options(
+            "test_config.xml".to_string(),
+            WatchOptions {
+                history_len: 0,
+                ..Default::default()
+            },
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_config_format() {
+        let path =
+            std::env::temp_dir().join(format!("test_update_toml_{}.toml", std::process::id()));
+        let config = ConfigFormat::Xml
+            .parse(&std::fs::read_to_string("test_config.xml").unwrap())
+            .unwrap();
+        std::fs::write(&path, ConfigFormat::Toml.write(&config).unwrap()).unwrap();
+
+        let service = ConfigurationService::new(path.to_str().unwrap().to_string()).await;
+        assert_eq!(service.subscribe().borrow().config, config);
+
+        let updated = service
+            .update(|config| {
+                config.validation_rules.groups.validation_rules_groups[0].validation_rules[0]
+                    .enabled = false;
+            })
+            .unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(ConfigFormat::Toml.parse(&written).unwrap(), updated.config);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_overlay_change_is_published() {
+        let path = scratch_config("test_layered_base");
+        let overlay_path =
+            std::env::temp_dir().join(format!("test_layered_office_{}.xml", std::process::id()));
+        let overlay = |enforce: &str| {
+            format!(
+                r#"<config><ValidationRules><Groups>
+                     <ValidationRulesGroup Group="Shift">
+                        <ValidationRule Type="SideJobPrevention">
+                           <Rules><Rule ForCandidateStatusIds="5" Enforce="{enforce}" /></Rules>
+                        </ValidationRule>
+                     </ValidationRulesGroup>
+                   </Groups></ValidationRules></config>"#
+            )
+        };
+        std::fs::write(&overlay_path, overlay("True")).unwrap();
+
+        let service = ConfigurationService::with_overlays(
+            path.clone(),
+            vec![overlay_path.to_str().unwrap().to_string()],
+            WatchOptions::default(),
+        )
+        .await;
+        let mut receiver = service.subscribe();
+        assert_eq!(
+            receiver.borrow().provenance.source_of(
+                "Shift",
+                &RuleType::SideJobPrevention,
+                "Rule[2].Enforce"
+            ),
+            overlay_path
+        );
+
+        std::fs::write(&overlay_path, overlay("False")).unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(3), receiver.changed())
+            .await
+            .expect("overlay change was not published")
+            .unwrap();
+
+        let snapshot = receiver.borrow_and_update().clone();
+        assert_eq!(
+            snapshot.diff.to_string(),
+            "~ Shift/SideJobPrevention Rule[2].Enforce: \"True\" -> \"False\""
+        );
+        // The base file itself is left alone
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            std::fs::read_to_string("test_config.xml").unwrap()
+        );
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(overlay_path).unwrap();
+    }
+
+    #[test]
+    fn test_history_is_limited() {
+        let config: Config =
+            from_str(&std::fs::read_to_string("test_config.xml").unwrap()).unwrap();
+        let options = WatchOptions {
+            history_len: 2,
+            ..WatchOptions::default()
+        };
+        let service = ConfigurationService::from_snapshot(
+            PathBuf::from("test_config.xml"),
+            ConfigFormat::Xml,
+            Vec::new(),
+            options,
+            snapshot(config.clone()),
+            String::new(),
+        );
+
+        for position_type_id in [10, 20] {
+            let mut changed = config.clone();
+            changed.validation_rules.groups.validation_rules_groups[0].validation_rules[0]
+                .position_type_ids = vec![position_type_id];
+            service.publish(layered(changed, &position_type_id.to_string()));
+        }
+
+        let revisions: Vec<u64> = service.history().iter().map(|s| s.revision).collect();
+        assert_eq!(revisions, vec![2, 3]);
+        assert!(matches!(
+            service.rollback_to(1),
+            Err(Error::UnknownRevision(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_restores_earlier_revision() {
+        let path = scratch_config("test_rollback");
+        let original = std::fs::read_to_string(&path).unwrap();
+        let history_dir = std::env::temp_dir().join(format!("test_history_{}", std::process::id()));
+        let options = WatchOptions {
+            history_dir: Some(history_dir.clone()),
+            ..WatchOptions::default()
+        };
+        let service = ConfigurationService::with_options(path.clone(), options).await;
+        let mut receiver = service.subscribe();
+        let first = receiver.borrow().clone();
+
+        std::fs::write(
+            &path,
+            original.replace(r#"Enabled="True""#, r#"Enabled="False""#),
+        )
+        .unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(3), receiver.changed())
+            .await
+            .expect("config change was not published")
+            .unwrap();
+        assert_eq!(receiver.borrow_and_update().revision, 2);
+
+        let restored = service.rollback_to(1).unwrap();
+
+        assert_eq!(restored.revision, 3);
+        assert_eq!(restored.config, first.config);
+        assert_eq!(restored.content_hash, first.content_hash);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+        assert_eq!(std::fs::read_dir(&history_dir).unwrap().count(), 3);
+
+        // The watcher picks up the rewritten file, but it matches the published config
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert_eq!(receiver.borrow_and_update().revision, 3);
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_dir_all(history_dir).unwrap();
+    }
+}
 
 /*Serde Deserializer DOCS: https://serde.rs/impl-deserialize.html*/
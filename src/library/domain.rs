@@ -0,0 +1,82 @@
+use chrono::{NaiveDateTime, TimeDelta};
+
+/// Candidate being validated together with the shifts already on their calendar
+/// (booked, worked or ended) - this is the history every rule evaluates against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub id: i32,
+    pub status_id: i32,
+    pub shifts: Vec<Shift>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shift {
+    pub id: i32,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub position_type_id: i32,
+    /// How far the candidate got in the matching process for this shift (0 = proposed).
+    pub match_status_id: i32,
+    pub shift_status_id: i32,
+    /// Set once the shift has ended, e.g. candidate no-show or cancelled by client.
+    pub end_reason_id: Option<i32>,
+}
+
+impl Shift {
+    pub fn duration(&self) -> TimeDelta {
+        self.end - self.start
+    }
+
+    /// Half-open interval check, back-to-back shifts do not overlap.
+    pub fn overlaps(&self, other: &Shift) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Book,
+    Cancel,
+}
+
+/// Single booking/cancel attempt made by `candidate` on `shift` at `requested_at`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationRequest {
+    /// Client / office whose rule set applies, `None` uses the single (non tenant) config.
+    pub tenant_id: Option<String>,
+    pub candidate: Candidate,
+    pub shift: Shift,
+    pub action: Action,
+    pub requested_at: NaiveDateTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    fn shift(start_hour: u32, hours: i64) -> Shift {
+        let start = NaiveDate::from_ymd_opt(2025, 2, 10)
+            .unwrap()
+            .and_hms_opt(start_hour, 0, 0)
+            .unwrap();
+        Shift {
+            id: 1,
+            start,
+            end: start + TimeDelta::hours(hours),
+            position_type_id: 1,
+            match_status_id: 0,
+            shift_status_id: 1,
+            end_reason_id: None,
+        }
+    }
+
+    #[test]
+    fn test_shift_overlaps() {
+        assert!(shift(8, 8).overlaps(&shift(12, 8)));
+        assert!(shift(12, 8).overlaps(&shift(8, 8)));
+        assert!(!shift(8, 4).overlaps(&shift(12, 4))); //back-to-back
+        assert_eq!(shift(20, 10).duration(), TimeDelta::hours(10)); //crosses midnight
+    }
+}
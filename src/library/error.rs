@@ -3,7 +3,7 @@ use derive_more::From;
 use quick_xml::{events::attributes::AttrError, DeError};
 
 use super::rule_types::RuleType;
-use crate::config::prelude::*;
+use crate::config::prelude::Location;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -12,6 +12,26 @@ pub type Result<T> = core::result::Result<T, Error>;
 #[derive(From, Debug)] 
 pub enum Error {
     ValidationError(RuleType),
+    /// Every problem `config::validator` found in the config file
+    InvalidConfig(Vec<Location>),
+    /// Overlay file that could not be parsed or applied on top of the base config
+    InvalidOverlay(std::path::PathBuf, Vec<Location>),
+    /// Requested revision is not (or no longer) part of the retained config history
+    UnknownRevision(u64),
+    /// No rule set is configured for the tenant of a validation request (`None` = request without tenant)
+    UnknownTenant(Option<String>),
+    /// Config of the tenant could not be loaded when the tenants were discovered
+    InvalidTenant(String, Box<Error>),
+    /// Two entries of the tenants directory map to the same tenant id
+    DuplicateTenant(String, std::path::PathBuf, std::path::PathBuf),
+    /// Config file extension is not one of `ConfigFormat::EXTENSIONS`
+    UnsupportedFormat(std::path::PathBuf),
+    /// `ConfigurationService::update` would have introduced these lints, nothing was written
+    RejectedUpdate(Vec<crate::config::lint::ConfigLint>),
+    /// `WatchOptions` a `ConfigurationService` can't run with
+    InvalidWatchOptions(String),
+    /// Config written to disk was not hot reloaded within the timeout
+    ReloadTimeout(std::time::Duration),
 
     //Other Module errors ...
     //#[from]
@@ -25,15 +45,45 @@ pub enum Error {
     #[from]
     XMLParsing(quick_xml::Error),
     #[from]
+    XMLSerializing(quick_xml::SeError),
+    #[from]
     Serde(DeError),
     // SerdeError(String, usize),
     #[from]
+    Toml(toml::de::Error),
+    #[from]
+    TomlSerializing(toml::ser::Error),
+    #[from]
+    Json(serde_json::Error),
+    #[from]
+    Yaml(serde_yaml::Error),
+    #[from]
     Tokio(tokio::time::error::Error),
+    #[from]
+    Notify(notify::Error),
 }
 
 impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::result::Result<(), core::fmt::Error> {
-        write!(f, "{:?}", self)
+        match self {
+            // One "Error at line X, column Y: ..." line per problem
+            Self::InvalidConfig(problems) => {
+                let lines: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            Self::InvalidOverlay(path, problems) => {
+                let lines: Vec<String> = problems
+                    .iter()
+                    .map(|p| format!("{}: {p}", path.display()))
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            Self::RejectedUpdate(lints) => {
+                let lines: Vec<String> = lints.iter().map(|l| l.to_string()).collect();
+                write!(f, "Update rejected:\n{}", lines.join("\n"))
+            }
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
@@ -139,6 +189,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_config_problems_are_listed_by_location() {
+        let problems = vec![
+            Location {
+                message: "Unknown attribute 'Minutes'".to_string(),
+                line: 4,
+                column: 12,
+            },
+            Location {
+                message: "Malformed XML".to_string(),
+                line: 9,
+                column: 1,
+            },
+        ];
+
+        assert_eq!(
+            Error::InvalidConfig(problems.clone()).to_string(),
+            "Error at line 4, column 12: Unknown attribute 'Minutes'\nError at line 9, column 1: Malformed XML"
+        );
+        assert_eq!(
+            Error::InvalidOverlay("office.xml".into(), problems).to_string(),
+            "office.xml: Error at line 4, column 12: Unknown attribute 'Minutes'\noffice.xml: Error at line 9, column 1: Malformed XML"
+        );
+    }
+
     #[test]
     fn test_serde_error() {
         let err = Error::Serde(quick_xml::DeError::Custom("Custom Error".into()));
@@ -0,0 +1,173 @@
+use crate::library::clock::{SharedClock, SystemClock};
+use crate::library::Result;
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+/// Settings of the configuration file watcher.
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Quiet period after the last file system event before the config is reloaded.
+    pub debounce: Duration,
+    /// Reload interval used when file system notifications are unavailable.
+    pub poll_interval: Duration,
+    /// When set, file contents rejected by a reload are copied here for later inspection.
+    pub quarantine_dir: Option<PathBuf>,
+    /// How many accepted revisions are kept around for `ConfigurationService::rollback_to`, at least 1.
+    pub history_len: usize,
+    /// When set, every accepted revision is also written here (pruned to `history_len` files).
+    pub history_dir: Option<PathBuf>,
+    /// "Now" of the service and of `RuleValidationService::evaluate_now`. `debounce` and `poll_interval`
+    /// are measured on it, an `AcceleratedClock` shortens them (down to `MIN_REAL_TIMER`).
+    pub clock: SharedClock,
+}
+
+/// Shortest real debounce / poll interval, however fast `WatchOptions::clock` runs: file writes
+/// still happen in real time and `tokio::time::interval` rejects a zero period.
+pub const MIN_REAL_TIMER: Duration = Duration::from_millis(10);
+
+impl WatchOptions {
+    /// Real time the watcher waits for `duration` on `clock`, at least `MIN_REAL_TIMER`.
+    pub fn real_duration(&self, duration: Duration) -> Duration {
+        self.clock.real_duration(duration).max(MIN_REAL_TIMER)
+    }
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(250),
+            poll_interval: Duration::from_secs(1),
+            quarantine_dir: None,
+            history_len: 10,
+            history_dir: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+/// Notifies about changes of a set of files (inotify on linux). </br>
+/// The parent directories are watched rather than the files themselves, so editors that save
+/// through a temp file + rename (atomic replace) keep being picked up.
+pub struct FileWatcher {
+    _watcher: RecommendedWatcher,
+    events: mpsc::UnboundedReceiver<()>,
+}
+
+impl FileWatcher {
+    pub fn new(path: &Path) -> Result<Self> {
+        Self::for_files(&[path.to_path_buf()])
+    }
+
+    /// Files are matched by name, an event for a same named file in another watched directory
+    /// only causes a spurious reload.
+    pub fn for_files(paths: &[PathBuf]) -> Result<Self> {
+        let file_names: Vec<OsString> = paths
+            .iter()
+            .filter_map(|path| path.file_name().map(|name| name.to_os_string()))
+            .collect();
+        let mut dirs: Vec<PathBuf> = paths
+            .iter()
+            .map(|path| match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => PathBuf::from("."),
+            })
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+
+        let (tx, events) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                Ok(event) => {
+                    let touches_file = event.paths.iter().any(|p| {
+                        p.file_name()
+                            .is_some_and(|name| file_names.iter().any(|n| n == name))
+                    });
+                    if touches_file && !event.kind.is_access() {
+                        let _ = tx.send(());
+                    }
+                }
+                Err(e) => eprintln!("--> File watcher ERROR: {e}"),
+            })?;
+        for dir in &dirs {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// Resolves once the file changed and no further events arrived for `debounce`. </br>
+    /// Returns `None` when the notification backend stopped.
+    pub async fn changed(&mut self, debounce: Duration) -> Option<()> {
+        self.events.recv().await?;
+
+        loop {
+            match tokio::time::timeout(debounce, self.events.recv()).await {
+                Ok(Some(())) => continue,
+                Ok(None) => return None,
+                Err(_quiet_period_elapsed) => return Some(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::clock::AcceleratedClock;
+    use chrono::NaiveDateTime;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_timers_follow_the_clock() {
+        let accelerated = |factor| WatchOptions {
+            clock: Arc::new(AcceleratedClock::new(NaiveDateTime::MIN, factor)),
+            ..Default::default()
+        };
+
+        let options = WatchOptions::default();
+        assert_eq!(
+            options.real_duration(options.poll_interval),
+            Duration::from_secs(1)
+        );
+        let options = accelerated(3600);
+        assert_eq!(
+            options.real_duration(Duration::from_secs(3600)),
+            Duration::from_secs(1)
+        );
+        // The 250ms debounce would shrink to nanoseconds and a zero poll period panics
+        let options = accelerated(u32::MAX);
+        assert_eq!(options.real_duration(options.debounce), MIN_REAL_TIMER);
+        assert_eq!(options.real_duration(Duration::ZERO), MIN_REAL_TIMER);
+    }
+
+    #[tokio::test]
+    async fn test_burst_of_writes_is_debounced() {
+        let path = std::env::temp_dir().join(format!("test_debounce_{}.xml", std::process::id()));
+        std::fs::write(&path, "<config />").unwrap();
+        let mut watcher = FileWatcher::new(&path).unwrap();
+
+        for i in 0..5 {
+            std::fs::write(&path, format!("<config id=\"{i}\" />")).unwrap();
+        }
+
+        let debounce = Duration::from_millis(100);
+        let first = tokio::time::timeout(Duration::from_secs(3), watcher.changed(debounce)).await;
+        assert!(first.is_ok());
+
+        let second =
+            tokio::time::timeout(Duration::from_millis(300), watcher.changed(debounce)).await;
+        assert!(second.is_err(), "writes were not coalesced into one change");
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
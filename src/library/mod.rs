@@ -1,10 +1,16 @@
+pub mod clock;
 pub mod configuration_service;
+pub mod file_watcher;
+pub mod reload_state;
 pub mod rule_validation_error;
 pub mod rule_validation_service;
+pub mod tenant_configuration_service;
 
-mod rule_types;
+mod domain;
 mod error;
+mod rule_types;
 
 //Flatten the module structure
+pub use domain::{Action, Candidate, Shift, ValidationRequest};
 pub use error::{Error, Result};
 pub use rule_types::RuleType;
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+
+/// Health of the config hot reload, see `ConfigurationService::subscribe_state`. </br>
+/// While reloads are failing subscribers of the config channel keep getting the last valid `Config`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReloadState {
+    /// Config file loaded and valid
+    Loaded,
+    /// Latest file contents were rejected, `since` is the first failure in a row.
+    ReloadFailed { error: String, since: DateTime<Utc> },
+    /// A valid config was loaded again after failing since `failed_since`.
+    /// Becomes `Loaded` with the next published config change.
+    Recovered {
+        failed_since: DateTime<Utc>,
+        recovered_at: DateTime<Utc>,
+    },
+}
+
+impl ReloadState {
+    pub fn failed(&self, error: String, now: DateTime<Utc>) -> Self {
+        let since = match self {
+            ReloadState::ReloadFailed { since, .. } => *since,
+            _ => now,
+        };
+        ReloadState::ReloadFailed { error, since }
+    }
+
+    /// `published` - the reload produced a config change
+    pub fn succeeded(&self, published: bool, now: DateTime<Utc>) -> Self {
+        match self {
+            ReloadState::ReloadFailed { since, .. } => ReloadState::Recovered {
+                failed_since: *since,
+                recovered_at: now,
+            },
+            ReloadState::Recovered { .. } if !published => self.clone(),
+            _ => ReloadState::Loaded,
+        }
+    }
+
+    /// Error of the rejected file contents, while the last good config is being served.
+    pub fn pending_error(&self) -> Option<&str> {
+        match self {
+            ReloadState::ReloadFailed { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_transitions() {
+        let t0 = Utc::now();
+        let t1 = t0 + TimeDelta::seconds(1);
+        let t2 = t0 + TimeDelta::seconds(2);
+
+        let failed = ReloadState::Loaded.failed("bad xml".to_string(), t0);
+        assert_eq!(failed.pending_error(), Some("bad xml"));
+
+        // Repeated failures keep the first `since`
+        let failed = failed.failed("still bad".to_string(), t1);
+        assert_eq!(
+            failed,
+            ReloadState::ReloadFailed {
+                error: "still bad".to_string(),
+                since: t0
+            }
+        );
+
+        let recovered = failed.succeeded(false, t2);
+        assert_eq!(
+            recovered,
+            ReloadState::Recovered {
+                failed_since: t0,
+                recovered_at: t2
+            }
+        );
+        assert_eq!(recovered.pending_error(), None);
+        assert_eq!(recovered.succeeded(false, t2), recovered);
+        assert_eq!(recovered.succeeded(true, t2), ReloadState::Loaded);
+    }
+}
@@ -11,6 +11,28 @@ pub enum RuleType {
     LastMinuteActionPreventionForCanceling,
 }
 
+impl RuleType {
+    pub const ALL: [RuleType; 5] = [
+        RuleType::SideJobPrevention,
+        RuleType::IndecisivePrevention,
+        RuleType::ExhaustionPrevention,
+        RuleType::LastMinuteActionPreventionForBooking,
+        RuleType::LastMinuteActionPreventionForCanceling,
+    ];
+
+    /// `ValidationRulesGroup` the rule belongs to - Candidate rules look at the candidate history only,
+    /// Shift rules at the shift being booked/cancelled.
+    pub fn group(&self) -> &'static str {
+        match self {
+            RuleType::IndecisivePrevention => "Candidate",
+            RuleType::SideJobPrevention
+            | RuleType::ExhaustionPrevention
+            | RuleType::LastMinuteActionPreventionForBooking
+            | RuleType::LastMinuteActionPreventionForCanceling => "Shift",
+        }
+    }
+}
+
 impl FromStr for RuleType {
     type Err = ();
 
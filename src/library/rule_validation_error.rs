@@ -1,6 +1,4 @@
-
-
-//NOTE:: THIS IS NOT ACTUALLY USED YET 
+use super::RuleType;
 
 #[derive(Debug)]
 pub struct RuleValidationError {
@@ -14,6 +12,44 @@ pub struct RuleValidationError {
 pub const ERR_CODE: u16 = 403;
 pub const FAIL_REASON: &str = "rule_validation";
 
+impl RuleValidationError {
+    /// `enforced == false` produces a warning, the action is still allowed (`success == true`).
+    pub fn new(rule_type: &RuleType, message: String, tag: String, enforced: bool) -> Self {
+        Self {
+            rule_name: format!("{:?}", rule_type),
+            failure_reason: FAIL_REASON.to_string(),
+            message,
+            tag,
+            success: !enforced,
+            error_code: ERR_CODE,
+        }
+    }
+
+    pub fn rule_name(&self) -> &str {
+        &self.rule_name
+    }
+
+    pub fn failure_reason(&self) -> &str {
+        &self.failure_reason
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn success(&self) -> bool {
+        self.success
+    }
+
+    pub fn error_code(&self) -> u16 {
+        self.error_code
+    }
+}
+
 impl std::fmt::Display for RuleValidationError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
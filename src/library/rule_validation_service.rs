@@ -1,27 +1,141 @@
-use crate::config::prelude::*;
-use crate::library::configuration_service::ValidationRulesGroupSettings;
-use crate::library::Result;
-use crate::{ConfigurationService, CONFIG_RULES};
+use crate::handlers;
+use crate::library::clock::{SharedClock, SystemClock};
+use crate::library::configuration_service::{
+    Config, ConfigSnapshot, ConfigurationService, ValidationRulesGroupSettings,
+};
+use crate::library::rule_validation_error::RuleValidationError;
+use crate::library::tenant_configuration_service::TenantConfigurationService;
+use crate::library::{Action, Candidate, Error, Result, Shift, ValidationRequest};
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
 use tokio::sync::watch;
 
 pub struct RuleValidationService {
-    config_rx: watch::Receiver<Config>,
+    /// Config of requests without `tenant_id`
+    config_rx: Option<watch::Receiver<ConfigSnapshot>>,
+    tenant_rx: BTreeMap<String, watch::Receiver<ConfigSnapshot>>,
+    /// Stamps the requests of `evaluate_now`
+    clock: SharedClock,
+}
+
+/// Result of running every enabled rule against a single `ValidationRequest`.
+#[derive(Debug, Default)]
+pub struct ValidationOutcome {
+    /// `ValidationRequest::tenant_id` whose rule set was used
+    pub tenant_id: Option<String>,
+    /// `ConfigSnapshot::revision` the rules were evaluated under
+    pub revision: u64,
+    /// Violations of enforced rules, any entry here blocks the action.
+    pub errors: Vec<RuleValidationError>,
+    /// Violations of rules with `Enforce="False"`, reported but not blocking.
+    pub warnings: Vec<RuleValidationError>,
+}
+
+impl ValidationOutcome {
+    pub fn is_allowed(&self) -> bool {
+        self.errors.is_empty()
+    }
 }
 
 impl RuleValidationService {
     pub async fn new(config_service: Arc<ConfigurationService>) -> Arc<Self> {
         let config_rx = config_service.subscribe();
-        Arc::new(Self { config_rx })
+        Arc::new(Self {
+            config_rx: Some(config_rx),
+            tenant_rx: BTreeMap::new(),
+            clock: Arc::clone(config_service.clock()),
+        })
+    }
+
+    /// Validates requests without tenant against a fixed `Config` (e.g. a what-if candidate), nothing is watched.
+    pub fn for_config(source: PathBuf, config: Config) -> Result<Arc<Self>> {
+        let (_tx, config_rx) = watch::channel(ConfigSnapshot::new(source, config)?);
+        Ok(Arc::new(Self {
+            config_rx: Some(config_rx),
+            tenant_rx: BTreeMap::new(),
+            clock: Arc::new(SystemClock),
+        }))
+    }
+
+    /// Requests are validated against the rule set of their `tenant_id`, requests without tenant are rejected.
+    pub async fn for_tenants(tenants: Arc<TenantConfigurationService>) -> Arc<Self> {
+        Arc::new(Self {
+            config_rx: None,
+            tenant_rx: tenants.subscribe(),
+            clock: tenants.clock(),
+        })
+    }
+
+    fn config_for(&self, tenant_id: Option<&str>) -> Result<&watch::Receiver<ConfigSnapshot>> {
+        match tenant_id {
+            Some(tenant_id) => self.tenant_rx.get(tenant_id),
+            None => self.config_rx.as_ref(),
+        }
+        .ok_or_else(|| Error::UnknownTenant(tenant_id.map(str::to_string)))
+    }
+
+    /// Validates the request against every rule of the current `Config` of its tenant that is enabled
+    /// and scheduled (`ValidationRuleSettings::schedule`) at `requested_at`.
+    pub fn evaluate(&self, request: &ValidationRequest) -> Result<ValidationOutcome> {
+        let snapshot = self.config_for(request.tenant_id.as_deref())?.borrow();
+        let mut outcome = ValidationOutcome {
+            tenant_id: request.tenant_id.clone(),
+            revision: snapshot.revision,
+            ..Default::default()
+        };
+
+        let enabled_rules = snapshot
+            .config
+            .validation_rules
+            .groups
+            .validation_rules_groups
+            .iter()
+            .flat_map(|group| &group.validation_rules)
+            .filter(|rule| rule.is_active_at(request.requested_at));
+
+        for settings in enabled_rules {
+            if let Some(violation) = handlers::evaluate(settings, request) {
+                if violation.success() {
+                    outcome.warnings.push(violation);
+                } else {
+                    outcome.errors.push(violation);
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Same as `evaluate` for a request made right now, `requested_at` is taken from the clock of the
+    /// config service (`WatchOptions::clock`).
+    pub fn evaluate_now(
+        &self,
+        tenant_id: Option<String>,
+        candidate: Candidate,
+        shift: Shift,
+        action: Action,
+    ) -> Result<ValidationOutcome> {
+        self.evaluate(&ValidationRequest {
+            tenant_id,
+            candidate,
+            shift,
+            action,
+            requested_at: self.clock.now(),
+        })
+    }
+
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
     }
 
     // Process a single set of rules to completion
     pub async fn process_rules(&self, task_name: &str) -> Result<()> {
         let rules_from_config: Vec<ValidationRulesGroupSettings> = self
-            .config_rx
+            .config_for(None)?
             .borrow()
+            .config
             .clone()
             .validation_rules
             .groups
@@ -32,7 +146,7 @@ impl RuleValidationService {
             task_name //, rules_from_config
         );
 
-        for rule in rules_from_config {
+        for _rule in rules_from_config {
             // println!("Task {} processing rule: {:?}", task_name, rule);
             println!("Task {} processing rule", task_name);
             //DO WORK HERE ...
@@ -46,3 +160,224 @@ impl RuleValidationService {
 
     //TODO: Implement a method to get specific rule by ID (enum RuleType)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::clock::FixedClock;
+    use crate::library::file_watcher::WatchOptions;
+    use chrono::{NaiveDate, TimeDelta};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_evaluate_without_violations() {
+        let config_service = ConfigurationService::new("test_config.xml".to_string()).await;
+        let service = RuleValidationService::new(config_service).await;
+
+        let start = NaiveDate::from_ymd_opt(2025, 2, 10)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        let request = ValidationRequest {
+            tenant_id: None,
+            candidate: Candidate {
+                id: 7,
+                status_id: 1,
+                shifts: vec![],
+            },
+            shift: Shift {
+                id: 1,
+                start,
+                end: start + TimeDelta::hours(8),
+                position_type_id: 1,
+                match_status_id: 0,
+                shift_status_id: 1,
+                end_reason_id: None,
+            },
+            action: Action::Book,
+            requested_at: start - TimeDelta::days(1),
+        };
+
+        let outcome = service.evaluate(&request).unwrap();
+
+        assert!(outcome.is_allowed());
+        assert_eq!(outcome.revision, 1);
+        assert_eq!(outcome.warnings.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_picks_tenant_rule_set() {
+        let disabled_path =
+            std::env::temp_dir().join(format!("test_tenant_disabled_{}.xml", std::process::id()));
+        std::fs::write(
+            &disabled_path,
+            std::fs::read_to_string("test_config.xml")
+                .unwrap()
+                .replace(r#"Enabled="True""#, r#"Enabled="False""#),
+        )
+        .unwrap();
+        let tenants = TenantConfigurationService::with_tenants(BTreeMap::from([
+            (
+                "strict".to_string(),
+                ConfigurationService::new("test_config.xml".to_string()).await,
+            ),
+            (
+                "lenient".to_string(),
+                ConfigurationService::new(disabled_path.to_str().unwrap().to_string()).await,
+            ),
+        ]));
+        let service = RuleValidationService::for_tenants(tenants).await;
+
+        // Booking a shift that overlaps one already on the calendar, only "strict" has SideJobPrevention enabled
+        let start = NaiveDate::from_ymd_opt(2025, 2, 10)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        let shift = |id, start| Shift {
+            id,
+            start,
+            end: start + TimeDelta::hours(8),
+            position_type_id: 1,
+            match_status_id: 0,
+            shift_status_id: 1,
+            end_reason_id: None,
+        };
+        let request = |tenant_id: Option<&str>| ValidationRequest {
+            tenant_id: tenant_id.map(str::to_string),
+            candidate: Candidate {
+                id: 7,
+                status_id: 1,
+                shifts: vec![shift(1, start)],
+            },
+            shift: shift(2, start + TimeDelta::hours(4)),
+            action: Action::Book,
+            requested_at: start - TimeDelta::days(1),
+        };
+
+        let strict = service.evaluate(&request(Some("strict"))).unwrap();
+        let lenient = service.evaluate(&request(Some("lenient"))).unwrap();
+
+        assert_eq!(strict.errors.len(), 1);
+        assert_eq!(lenient.tenant_id.as_deref(), Some("lenient"));
+        assert!(lenient.is_allowed());
+        assert!(matches!(
+            service.evaluate(&request(Some("unknown"))),
+            Err(Error::UnknownTenant(Some(_)))
+        ));
+        assert!(matches!(
+            service.evaluate(&request(None)),
+            Err(Error::UnknownTenant(None))
+        ));
+
+        std::fs::remove_file(disabled_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_honors_rule_schedule() {
+        let scheduled_path =
+            std::env::temp_dir().join(format!("test_scheduled_{}.xml", std::process::id()));
+        std::fs::write(
+            &scheduled_path,
+            std::fs::read_to_string("test_config.xml").unwrap().replace(
+                r#"Type="SideJobPrevention""#,
+                r#"Type="SideJobPrevention" EffectiveFrom="2025-11-01" InactiveWindows="12-24..01-02""#,
+            ),
+        )
+        .unwrap();
+        let config_service =
+            ConfigurationService::new(scheduled_path.to_str().unwrap().to_string()).await;
+        let service = RuleValidationService::new(config_service).await;
+
+        // Overlapping booking, only a violation while SideJobPrevention is scheduled
+        let overlapping_booking = |date: NaiveDate| {
+            let start = date.and_hms_opt(8, 0, 0).unwrap();
+            let shift = |id, start| Shift {
+                id,
+                start,
+                end: start + TimeDelta::hours(8),
+                position_type_id: 1,
+                match_status_id: 0,
+                shift_status_id: 1,
+                end_reason_id: None,
+            };
+            ValidationRequest {
+                tenant_id: None,
+                candidate: Candidate {
+                    id: 7,
+                    status_id: 1,
+                    shifts: vec![shift(1, start)],
+                },
+                shift: shift(2, start + TimeDelta::hours(4)),
+                action: Action::Book,
+                requested_at: start - TimeDelta::days(1),
+            }
+        };
+        let errors = |y, m, d| {
+            let request = overlapping_booking(NaiveDate::from_ymd_opt(y, m, d).unwrap());
+            service.evaluate(&request).unwrap().errors.len()
+        };
+
+        assert_eq!(errors(2025, 10, 20), 0); // before EffectiveFrom
+        assert_eq!(errors(2025, 11, 20), 1);
+        assert_eq!(errors(2025, 12, 28), 0); // holidays
+        assert_eq!(errors(2026, 1, 10), 1);
+
+        std::fs::remove_file(scheduled_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_now_uses_the_clock() {
+        let last_minute_path =
+            std::env::temp_dir().join(format!("test_last_minute_{}.xml", std::process::id()));
+        std::fs::write(
+            &last_minute_path,
+            r#"<config><ValidationRules><Groups><ValidationRulesGroup Group="Shift">
+                <ValidationRule Type="LastMinuteActionPreventionForBooking" Enabled="True">
+                    <Rules><Rule ForCandidateStatusIds="" Minutes="60" Enforce="True" /></Rules>
+                </ValidationRule>
+            </ValidationRulesGroup></Groups></ValidationRules></config>"#,
+        )
+        .unwrap();
+        let start = NaiveDate::from_ymd_opt(2025, 2, 10)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        let clock = Arc::new(FixedClock::new(start - TimeDelta::minutes(30)));
+        let config_service = ConfigurationService::with_options(
+            last_minute_path.to_str().unwrap().to_string(),
+            WatchOptions {
+                clock: clock.clone(),
+                ..Default::default()
+            },
+        )
+        .await;
+        let service = RuleValidationService::new(config_service).await;
+        let book = || {
+            let candidate = Candidate {
+                id: 7,
+                status_id: 1,
+                shifts: vec![],
+            };
+            let shift = Shift {
+                id: 1,
+                start,
+                end: start + TimeDelta::hours(8),
+                position_type_id: 1,
+                match_status_id: 0,
+                shift_status_id: 1,
+                end_reason_id: None,
+            };
+            service
+                .evaluate_now(None, candidate, shift, Action::Book)
+                .unwrap()
+        };
+
+        assert_eq!(service.clock().now(), start - TimeDelta::minutes(30));
+        assert!(!book().is_allowed());
+
+        clock.set(start - TimeDelta::hours(2));
+        assert!(book().is_allowed());
+
+        std::fs::remove_file(last_minute_path).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+use crate::library::clock::{SharedClock, SystemClock};
+use crate::library::configuration_service::{ConfigFormat, ConfigSnapshot, ConfigurationService};
+use crate::library::file_watcher::WatchOptions;
+use crate::library::{Error, Result};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// File stem of the base config inside a tenant directory (`config.xml`, `config.toml`, ...),
+/// the other `*.xml` files are its overlays.
+pub const TENANT_BASE_CONFIG: &str = "config";
+
+/// Rule sets of several tenants (clients / offices), keyed by tenant id. </br>
+/// Every tenant is served by its own `ConfigurationService`, so each one is watched and
+/// hot reloaded independently - a broken file of one tenant never affects the others.
+#[derive(Debug)]
+pub struct TenantConfigurationService {
+    tenants: BTreeMap<String, Arc<ConfigurationService>>,
+}
+
+impl TenantConfigurationService {
+    /// One tenant per entry of `dir`:
+    /// - `<tenant>.xml` (or any other `ConfigFormat`) is a single config file
+    /// - `<tenant>/config.xml` is a base config, the other `<tenant>/*.xml` files are applied on top of it
+    ///   as overlays in file name order
+    ///
+    /// Tenants are discovered once, files added to `dir` later are not picked up. </br>
+    /// Fails when the config of a tenant doesn't load, or when two entries share a tenant id
+    /// (e.g. `acme.xml` and `acme.toml`).
+    pub async fn from_dir(dir: &Path, watch_options: WatchOptions) -> Result<Arc<Self>> {
+        let mut sources: BTreeMap<String, (PathBuf, PathBuf, Vec<String>)> = BTreeMap::new();
+
+        for path in sorted_entries(dir)? {
+            let Some(tenant_id) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+
+            let (base, overlays) = if path.is_dir() {
+                let Some(base) = sorted_entries(&path)?.into_iter().find(|p| {
+                    is_config(p) && p.file_stem().is_some_and(|s| s == TENANT_BASE_CONFIG)
+                }) else {
+                    eprintln!(
+                        "--> Skipping tenant directory {} without {TENANT_BASE_CONFIG} file",
+                        path.display()
+                    );
+                    continue;
+                };
+                let overlays = sorted_entries(&path)?
+                    .into_iter()
+                    .filter(|p| {
+                        *p != base
+                            && ConfigFormat::from_path(p).is_ok_and(|f| f == ConfigFormat::Xml)
+                    })
+                    .map(|p| p.to_string_lossy().to_string())
+                    .collect();
+                (base, overlays)
+            } else if is_config(&path) {
+                (path.clone(), Vec::new())
+            } else {
+                continue;
+            };
+
+            if let Some((other, _, _)) = sources.get(&tenant_id) {
+                return Err(Error::DuplicateTenant(tenant_id, other.clone(), path));
+            }
+            sources.insert(tenant_id, (path, base, overlays));
+        }
+
+        let mut tenants = BTreeMap::new();
+        for (tenant_id, (_, base, overlays)) in sources {
+            let service = ConfigurationService::try_with_overlays(
+                base.to_string_lossy().to_string(),
+                overlays,
+                watch_options.clone(),
+            )
+            .await
+            .map_err(|e| Error::InvalidTenant(tenant_id.clone(), Box::new(e)))?;
+            tenants.insert(tenant_id, service);
+        }
+
+        Ok(Arc::new(Self { tenants }))
+    }
+
+    pub fn with_tenants(tenants: BTreeMap<String, Arc<ConfigurationService>>) -> Arc<Self> {
+        Arc::new(Self { tenants })
+    }
+
+    pub fn tenant(&self, tenant_id: &str) -> Option<&Arc<ConfigurationService>> {
+        self.tenants.get(tenant_id)
+    }
+
+    pub fn tenant_ids(&self) -> impl Iterator<Item = &str> {
+        self.tenants.keys().map(String::as_str)
+    }
+
+    /// Clock of the first tenant, the tenants of `from_dir` share the one of their `WatchOptions`.
+    pub fn clock(&self) -> SharedClock {
+        self.tenants
+            .values()
+            .next()
+            .map(|service| Arc::clone(service.clock()))
+            .unwrap_or_else(|| Arc::new(SystemClock))
+    }
+
+    /// Config channel of every tenant.
+    pub fn subscribe(&self) -> BTreeMap<String, watch::Receiver<ConfigSnapshot>> {
+        self.tenants
+            .iter()
+            .map(|(tenant_id, service)| (tenant_id.clone(), service.subscribe()))
+            .collect()
+    }
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        paths.push(entry?.path());
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn is_config(path: &Path) -> bool {
+    path.is_file() && ConfigFormat::from_path(path).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::RuleType;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_tenants_are_reloaded_independently() {
+        let dir = std::env::temp_dir().join(format!("test_tenants_{}", std::process::id()));
+        let office_dir = dir.join("office_b");
+        std::fs::create_dir_all(&office_dir).unwrap();
+        std::fs::copy("test_config.xml", dir.join("client_a.xml")).unwrap();
+        std::fs::copy("test_config.xml", office_dir.join("config.xml")).unwrap();
+        std::fs::write(
+            office_dir.join("thresholds.xml"),
+            r#"<config><ValidationRules><Groups>
+                  <ValidationRulesGroup Group="Shift">
+                     <ValidationRule Type="SideJobPrevention" Enabled="False" />
+                  </ValidationRulesGroup>
+               </Groups></ValidationRules></config>"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a tenant").unwrap();
+
+        let tenants = TenantConfigurationService::from_dir(&dir, WatchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            tenants.tenant_ids().collect::<Vec<_>>(),
+            vec!["client_a", "office_b"]
+        );
+
+        let mut receivers = tenants.subscribe();
+        let enabled = |snapshot: &ConfigSnapshot| {
+            snapshot
+                .config
+                .validation_rules
+                .groups
+                .validation_rules_groups[0]
+                .validation_rules[0]
+                .enabled
+        };
+        assert_eq!(enabled(&receivers["client_a"].borrow()), true);
+        assert_eq!(enabled(&receivers["office_b"].borrow()), false);
+        assert_eq!(
+            receivers["office_b"].borrow().provenance.source_of(
+                "Shift",
+                &RuleType::SideJobPrevention,
+                "Enabled"
+            ),
+            office_dir.join("thresholds.xml")
+        );
+
+        let client_a = dir.join("client_a.xml");
+        let updated = std::fs::read_to_string(&client_a)
+            .unwrap()
+            .replace(r#"PositionTypeIDs="1""#, r#"PositionTypeIDs="1,2""#);
+        std::fs::write(&client_a, updated).unwrap();
+
+        let client_rx = receivers.get_mut("client_a").unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(3), client_rx.changed())
+            .await
+            .expect("tenant change was not published")
+            .unwrap();
+        assert_eq!(client_rx.borrow_and_update().revision, 2);
+        assert_eq!(receivers["office_b"].has_changed().unwrap(), false);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_broken_tenant_is_reported() {
+        let dir = std::env::temp_dir().join(format!("test_broken_tenant_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("test_config.xml", dir.join("client_a.xml")).unwrap();
+        std::fs::write(dir.join("client_b.xml"), "<config><ValidationRules>").unwrap();
+
+        let result = TenantConfigurationService::from_dir(&dir, WatchOptions::default()).await;
+        assert!(
+            matches!(&result, Err(Error::InvalidTenant(tenant_id, _)) if tenant_id == "client_b"),
+            "{result:?}"
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_tenant_ids_are_rejected() {
+        let dir =
+            std::env::temp_dir().join(format!("test_duplicate_tenant_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("acme")).unwrap();
+        std::fs::copy("test_config.xml", dir.join("acme").join("config.xml")).unwrap();
+        std::fs::copy("test_config.xml", dir.join("acme.xml")).unwrap();
+
+        let result = TenantConfigurationService::from_dir(&dir, WatchOptions::default()).await;
+        match result {
+            Err(Error::DuplicateTenant(tenant_id, first, second)) => {
+                assert_eq!(tenant_id, "acme");
+                assert_eq!((first, second), (dir.join("acme"), dir.join("acme.xml")));
+            }
+            other => panic!("expected a duplicate tenant, got {other:?}"),
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_empty_history_is_reported() {
+        let dir = std::env::temp_dir().join(format!("test_tenant_history_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("test_config.xml", dir.join("client_a.xml")).unwrap();
+        let watch_options = WatchOptions {
+            history_len: 0,
+            ..Default::default()
+        };
+
+        let result = TenantConfigurationService::from_dir(&dir, watch_options).await;
+        assert!(
+            matches!(
+                &result,
+                Err(Error::InvalidTenant(tenant_id, e))
+                    if tenant_id == "client_a" && matches!(**e, Error::InvalidWatchOptions(_))
+            ),
+            "{result:?}"
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,12 +1,11 @@
-mod config;
-mod library;
-
-use config::config::{ValidationRuleSettings, ValidationRulesGroupSettings};
-use library::{
+use std::sync::{Arc, LazyLock};
+use validation_state_engine::config::config::{
+    ValidationRuleSettings, ValidationRulesGroupSettings,
+};
+use validation_state_engine::library::{
     configuration_service::ConfigurationService, rule_validation_service::RuleValidationService,
     RuleType,
 };
-use std::sync::{Arc, LazyLock};
 
 static CONFIG_RULES: LazyLock<Vec<RuleType>> = LazyLock::new(|| {
     vec![
@@ -37,9 +36,10 @@ async fn main() -> ! {
     loop {
         //Wait for configuration changes / Spawn task handlers for processing Rule changes
         if config_rx.changed().await.is_ok() {
-            let config_clone = config_rx.borrow().clone();
+            let snapshot = config_rx.borrow_and_update().clone();
 
-            let validation_rule_groups: Vec<Vec<ValidationRuleSettings>> = config_clone
+            let validation_rule_groups: Vec<Vec<ValidationRuleSettings>> = snapshot
+                .config
                 .validation_rules
                 .groups
                 .validation_rules_groups
@@ -48,7 +48,16 @@ async fn main() -> ! {
                 .filter(|x: &Vec<ValidationRuleSettings>| !x.is_empty())
                 .collect();
 
-            println!("Fetched {:#?} ", validation_rule_groups);
+            println!(
+                "Fetched revision {} ({}) with {} configured rule groups, changes:\n{}",
+                snapshot.revision,
+                snapshot.content_hash,
+                validation_rule_groups.len(),
+                snapshot.diff
+            );
+            if snapshot.provenance.is_overridden() {
+                println!("Overridden values:\n{}", snapshot.provenance);
+            }
 
             println!(
                 "\nConfiguration change detected - spawning {} new tasks ...",
@@ -57,7 +66,7 @@ async fn main() -> ! {
 
             let mut tasks = Vec::with_capacity(cfg.len());
 
-            for (_i, rule) in cfg.iter().enumerate() {
+            for rule in cfg.iter() {
                 let service_clone = Arc::clone(&service);
 
                 let task_name = format!("{:?}", rule); //temp placeholder
@@ -0,0 +1,646 @@
+use super::engine::{rule_names, Simulation};
+use super::report::SimulationReport;
+use super::workload::Workload;
+use crate::config::prelude::{AttributeKind, Config, Rule, ValidationRuleSettings};
+use crate::library::clock::SharedClock;
+use crate::library::configuration_service::{ConfigSnapshot, ConfigurationService};
+use crate::library::rule_validation_service::RuleValidationService;
+use crate::library::{Error, Result, RuleType, ValidationRequest};
+
+use chrono::{NaiveDateTime, NaiveTime, TimeDelta};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Config change made while the simulated day is running.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mutation {
+    ToggleEnabled {
+        group: String,
+        rule_type: RuleType,
+    },
+    /// Flips `Enforce` of the `rule`-th `<Rule>`, unset counts as not enforced
+    ToggleEnforce {
+        group: String,
+        rule_type: RuleType,
+        rule: usize,
+    },
+    /// Sets an attribute of the `rule`-th `<Rule>`, e.g. the `HoursAllowed` / `Minutes` thresholds
+    SetRuleAttribute {
+        group: String,
+        rule_type: RuleType,
+        rule: usize,
+        attribute: String,
+        value: String,
+    },
+}
+
+impl Mutation {
+    /// Fails when the targeted rule does not exist or the value is rejected by `Rule::set_attribute`.
+    pub fn apply(&self, config: &mut Config) -> std::result::Result<(), String> {
+        match self {
+            Mutation::ToggleEnabled { group, rule_type } => {
+                let settings = settings_mut(config, group, rule_type)?;
+                settings.enabled = !settings.enabled;
+                Ok(())
+            }
+            Mutation::ToggleEnforce {
+                group,
+                rule_type,
+                rule,
+            } => {
+                let rule = rule_mut(config, group, rule_type, *rule)?;
+                let enforce = if rule.enforce() { "False" } else { "True" };
+                rule.set_attribute("Enforce", enforce)
+            }
+            Mutation::SetRuleAttribute {
+                group,
+                rule_type,
+                rule,
+                attribute,
+                value,
+            } => rule_mut(config, group, rule_type, *rule)?.set_attribute(attribute, value),
+        }
+    }
+
+    /// A toggle or threshold change of a rule picked from `config`, `None` when it has no rules. </br>
+    /// Thresholds (`Int` attributes of a `<Rule>`) are set anywhere between 1 and twice their
+    /// current value (24 when unset).
+    fn random(rng: &mut ChaCha8Rng, config: &Config) -> Option<Mutation> {
+        let targets: Vec<(&str, &ValidationRuleSettings)> = config
+            .validation_rules
+            .groups
+            .validation_rules_groups
+            .iter()
+            .flat_map(|group| {
+                group
+                    .validation_rules
+                    .iter()
+                    .map(|settings| (group.group.as_str(), settings))
+            })
+            .collect();
+        if targets.is_empty() {
+            return None;
+        }
+        let (group, settings) = targets[rng.random_range(0..targets.len())];
+        let (group, rule_type) = (group.to_string(), settings.rule_type.clone());
+        let rules = &settings.rules.rules;
+
+        match rng.random_range(0..3) {
+            1 if !rules.is_empty() => Some(Mutation::ToggleEnforce {
+                group,
+                rule_type,
+                rule: rng.random_range(0..rules.len()),
+            }),
+            2 if !rules.is_empty() => {
+                let rule = rng.random_range(0..rules.len());
+                let thresholds: Vec<(&str, String)> = rules[rule]
+                    .attributes()
+                    .into_iter()
+                    .filter(|(name, _)| AttributeKind::of(name) == AttributeKind::Int)
+                    .collect();
+                if thresholds.is_empty() {
+                    return Some(Mutation::ToggleEnabled { group, rule_type });
+                }
+                let (attribute, current) = &thresholds[rng.random_range(0..thresholds.len())];
+                let current = current.parse::<i32>().unwrap_or(24).max(1);
+                let mut value = rng.random_range(1..=current.saturating_mul(2));
+                if value == current {
+                    value = current.checked_add(1).unwrap_or(current - 1);
+                }
+                Some(Mutation::SetRuleAttribute {
+                    group,
+                    rule_type,
+                    rule,
+                    attribute: attribute.to_string(),
+                    value: value.to_string(),
+                })
+            }
+            _ => Some(Mutation::ToggleEnabled { group, rule_type }),
+        }
+    }
+}
+
+fn settings_mut<'a>(
+    config: &'a mut Config,
+    group: &str,
+    rule_type: &RuleType,
+) -> std::result::Result<&'a mut ValidationRuleSettings, String> {
+    config
+        .validation_rules
+        .groups
+        .validation_rules_groups
+        .iter_mut()
+        .filter(|settings| settings.group == group)
+        .flat_map(|settings| &mut settings.validation_rules)
+        .find(|settings| &settings.rule_type == rule_type)
+        .ok_or_else(|| format!("No {rule_type:?} rule in group {group}"))
+}
+
+fn rule_mut<'a>(
+    config: &'a mut Config,
+    group: &str,
+    rule_type: &RuleType,
+    rule: usize,
+) -> std::result::Result<&'a mut Rule, String> {
+    settings_mut(config, group, rule_type)?
+        .rules
+        .rules
+        .get_mut(rule)
+        .ok_or_else(|| format!("{group}/{rule_type:?} has no Rule[{rule}]"))
+}
+
+/// Mutation applied at a fixed time of the simulated day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledMutation {
+    pub at: NaiveDateTime,
+    pub mutation: Mutation,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChaosOptions {
+    /// Seed of the random mutations and of their times
+    pub seed: u64,
+    /// Mutations picked at random from the config, spread over the day on top of `schedule`
+    pub random_mutations: usize,
+    pub schedule: Vec<ScheduledMutation>,
+    /// Wait for every written mutation to be hot reloaded before the next event is replayed. </br>
+    /// Without waiting, reloads land while the following requests are being validated.
+    pub wait_for_reload: bool,
+    pub reload_timeout: Duration,
+    /// Replay every event (and write every mutation) once the clock of the config service reaches it,
+    /// instead of as fast as possible. With an `AcceleratedClock` starting at the beginning of the day,
+    /// the day passes in seconds and reloads (debounced on the same clock) land mid-day.
+    pub realtime: bool,
+}
+
+impl Default for ChaosOptions {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            random_mutations: 5,
+            schedule: Vec::new(),
+            wait_for_reload: true,
+            reload_timeout: Duration::from_secs(5),
+            realtime: false,
+        }
+    }
+}
+
+/// Mutation as it was applied, `error` when it was rejected and nothing was written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedMutation {
+    pub at: NaiveDateTime,
+    pub mutation: Mutation,
+    /// Revision serving the mutated config, `None` when it was not waited for (or changed nothing)
+    pub revision: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Outcome of `chaos`, the simulated day and how its decisions relate to the config revisions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosReport {
+    pub simulation: SimulationReport,
+    pub mutations: Vec<AppliedMutation>,
+    /// Every revision a decision was made under, in order of first use
+    pub revisions: Vec<u64>,
+    /// `Decision::index` of decisions that differ from a re-evaluation against the config of their revision
+    pub inconsistent: Vec<usize>,
+    /// Decisions whose revision was neither observed nor retained in the history, so not re-evaluated
+    pub unverified: usize,
+}
+
+impl ChaosReport {
+    /// Every decision matches its revision and revisions never went back during the day.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistent.is_empty() && self.revisions.windows(2).all(|pair| pair[0] < pair[1])
+    }
+}
+
+/// Replays `workload` against the config served by `config_service` while mutating its file (see
+/// `ConfigurationService::edit_file`), so every change travels the hot reload path. </br>
+/// Every decision is re-evaluated against the config of the revision it reported, a validation that
+/// mixed two revisions shows up in `ChaosReport::inconsistent`.
+/// The base config file is rewritten, run this against a copy. Its `tenant_id` is ignored like in `compare`.
+pub async fn chaos(
+    config_service: Arc<ConfigurationService>,
+    workload: &Workload,
+    options: &ChaosOptions,
+) -> Result<ChaosReport> {
+    let mut rx = config_service.subscribe();
+    let service = RuleValidationService::new(config_service.clone()).await;
+    // In real time the requests are stamped by the clock `wait_until` sleeps against
+    let mut simulation = if options.realtime {
+        Simulation::with_clock(service, workload.day, config_service.clock().clone())
+    } else {
+        Simulation::new(service, workload.day)
+    };
+    for candidate in &workload.candidates {
+        simulation.add_candidate(None, candidate.clone());
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(options.seed);
+    let day_start = workload.day.and_time(NaiveTime::MIN);
+    let mut pending: Vec<(NaiveDateTime, Option<Mutation>)> = options
+        .schedule
+        .iter()
+        .map(|scheduled| (scheduled.at, Some(scheduled.mutation.clone())))
+        .chain((0..options.random_mutations).map(|_| {
+            let second = rng.random_range(0..24 * 60 * 60);
+            (day_start + TimeDelta::seconds(second), None)
+        }))
+        .collect();
+    pending.sort_by_key(|(at, _)| *at);
+    let mut pending = pending.into_iter().peekable();
+
+    let mut report = ChaosReport {
+        simulation: SimulationReport::new(workload.day),
+        mutations: Vec::new(),
+        revisions: Vec::new(),
+        inconsistent: Vec::new(),
+        unverified: 0,
+    };
+    let mut requests = Vec::new();
+    let mut observed: BTreeMap<u64, ConfigSnapshot> = BTreeMap::new();
+    let mut expected = None;
+
+    let events = simulation.timeline(workload.events.iter().cloned().map(|mut event| {
+        event.tenant_id = None;
+        event
+    }));
+    for (index, event) in events.into_iter().enumerate() {
+        while let Some((at, mutation)) = pending.next_if(|(at, _)| *at <= event.at) {
+            if options.realtime {
+                wait_until(config_service.clock(), at).await;
+            }
+            let applied = mutate(&config_service, &mut rx, &mut rng, at, mutation, options).await?;
+            if let Some((applied, config)) = applied {
+                report.mutations.push(applied);
+                expected = config.or(expected);
+            }
+        }
+        if options.realtime {
+            wait_until(config_service.clock(), event.at).await;
+        } else {
+            tokio::task::yield_now().await;
+        }
+
+        if let Some(request) = simulation.replay(index, event, &mut report.simulation)? {
+            requests.push(request);
+            let snapshot = rx.borrow();
+            observed
+                .entry(snapshot.revision)
+                .or_insert_with(|| snapshot.clone());
+        }
+    }
+    for (at, mutation) in pending {
+        let applied = mutate(&config_service, &mut rx, &mut rng, at, mutation, options).await?;
+        if let Some((applied, config)) = applied {
+            report.mutations.push(applied);
+            expected = config.or(expected);
+        }
+    }
+    if let Some(config) = expected {
+        reloaded(&mut rx, &config, options.reload_timeout).await?;
+    }
+
+    for snapshot in config_service.history() {
+        observed.entry(snapshot.revision).or_insert(snapshot);
+    }
+    verify(&mut report, &requests, &observed)?;
+    Ok(report)
+}
+
+async fn wait_until(clock: &SharedClock, at: NaiveDateTime) {
+    if let Ok(ahead) = (at - clock.now()).to_std() {
+        tokio::time::sleep(clock.real_duration(ahead)).await;
+    }
+}
+
+/// Writes one mutation, `None` when there was nothing to mutate. The config is returned when the file
+/// was written, it is what the watcher is expected to publish.
+async fn mutate(
+    config_service: &ConfigurationService,
+    rx: &mut watch::Receiver<ConfigSnapshot>,
+    rng: &mut ChaCha8Rng,
+    at: NaiveDateTime,
+    mutation: Option<Mutation>,
+    options: &ChaosOptions,
+) -> Result<Option<(AppliedMutation, Option<Config>)>> {
+    let current = rx.borrow().config.clone();
+    let Some(mutation) = mutation.or_else(|| Mutation::random(rng, &current)) else {
+        return Ok(None);
+    };
+    let mut applied = AppliedMutation {
+        at,
+        mutation,
+        revision: None,
+        error: None,
+    };
+
+    // Applied to the file, the served config can differ from it (overlays, external edits)
+    let config = match config_service.edit_file(|config| applied.mutation.apply(config))? {
+        Ok(config) => config,
+        Err(e) => {
+            applied.error = Some(e);
+            return Ok(Some((applied, None)));
+        }
+    };
+    if options.wait_for_reload {
+        applied.revision = reloaded(rx, &config, options.reload_timeout).await?;
+    }
+    Ok(Some((applied, Some(config))))
+}
+
+/// Waits until `config` is published, returns its revision (`None` when it was already being served).
+async fn reloaded(
+    rx: &mut watch::Receiver<ConfigSnapshot>,
+    config: &Config,
+    timeout: Duration,
+) -> Result<Option<u64>> {
+    if rx.borrow_and_update().config == *config {
+        return Ok(None);
+    }
+    let wait = async {
+        loop {
+            rx.changed().await.map_err(|e| Error::Std(Box::new(e)))?;
+            let snapshot = rx.borrow_and_update();
+            if snapshot.config == *config {
+                return Ok(Some(snapshot.revision));
+            }
+        }
+    };
+    tokio::time::timeout(timeout, wait)
+        .await
+        .map_err(|_| Error::ReloadTimeout(timeout))?
+}
+
+/// Re-evaluates every decision against the config of its revision.
+fn verify(
+    report: &mut ChaosReport,
+    requests: &[ValidationRequest],
+    observed: &BTreeMap<u64, ConfigSnapshot>,
+) -> Result<()> {
+    let mut services = BTreeMap::new();
+    for (decision, request) in report.simulation.decisions.iter().zip(requests) {
+        if report.revisions.last() != Some(&decision.revision) {
+            report.revisions.push(decision.revision);
+        }
+        let Some(snapshot) = observed.get(&decision.revision) else {
+            report.unverified += 1;
+            continue;
+        };
+        let service = match services.entry(decision.revision) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(RuleValidationService::for_config(
+                snapshot.source.clone(),
+                snapshot.config.clone(),
+            )?),
+        };
+        let outcome = service.evaluate(request)?;
+        if outcome.is_allowed() != decision.allowed
+            || rule_names(&outcome.errors) != decision.blocked_by
+            || rule_names(&outcome.warnings) != decision.warned_by
+        {
+            report.inconsistent.push(decision.index);
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mutation::ToggleEnabled { group, rule_type } => {
+                write!(f, "toggle Enabled of {group}/{rule_type:?}")
+            }
+            Mutation::ToggleEnforce {
+                group,
+                rule_type,
+                rule,
+            } => write!(f, "toggle Enforce of {group}/{rule_type:?} Rule[{rule}]"),
+            Mutation::SetRuleAttribute {
+                group,
+                rule_type,
+                rule,
+                attribute,
+                value,
+            } => write!(
+                f,
+                "set {group}/{rule_type:?} Rule[{rule}] {attribute}=\"{value}\""
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ChaosReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.simulation)?;
+        let revisions: Vec<String> = self.revisions.iter().map(u64::to_string).collect();
+        writeln!(
+            f,
+            "Chaos: {} mutations, decisions made under revisions {}, {} inconsistent, {} unverified -> {}",
+            self.mutations.len(),
+            revisions.join(", "),
+            self.inconsistent.len(),
+            self.unverified,
+            if self.is_consistent() {
+                "consistent"
+            } else {
+                "INCONSISTENT"
+            }
+        )?;
+        for applied in &self.mutations {
+            let outcome = match (&applied.error, applied.revision) {
+                (Some(e), _) => format!("rejected: {e}"),
+                (None, Some(revision)) => format!("revision {revision}"),
+                (None, None) => "written".to_string(),
+            };
+            writeln!(
+                f,
+                "   {} {} ({outcome})",
+                applied.at.format("%H:%M:%S"),
+                applied.mutation
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::prelude::ConfigFormat;
+    use crate::library::clock::AcceleratedClock;
+    use crate::library::file_watcher::WatchOptions;
+    use crate::simulation::WorkloadOptions;
+    use pretty_assertions::assert_eq;
+
+    /// Copy of `test_config.xml` the chaos run is free to rewrite.
+    async fn scratch_service(
+        name: &str,
+        watch_options: WatchOptions,
+    ) -> (Arc<ConfigurationService>, String) {
+        let path = std::env::temp_dir().join(format!("{}_{}.xml", name, std::process::id()));
+        std::fs::copy("test_config.xml", &path).unwrap();
+        let path = path.to_str().unwrap().to_string();
+        let service = ConfigurationService::with_options(path.clone(), watch_options).await;
+        (service, path)
+    }
+
+    fn side_job() -> (String, RuleType) {
+        ("Shift".to_string(), RuleType::SideJobPrevention)
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_mutations_are_hot_reloaded() {
+        let (service, path) = scratch_service(
+            "chaos_scheduled",
+            WatchOptions {
+                debounce: Duration::from_millis(50),
+                ..Default::default()
+            },
+        )
+        .await;
+        let workload = Workload::generate(&WorkloadOptions::default());
+        let noon = workload.day.and_hms_opt(12, 0, 0).unwrap();
+        let (group, rule_type) = side_job();
+        let options = ChaosOptions {
+            random_mutations: 0,
+            schedule: vec![
+                ScheduledMutation {
+                    at: noon,
+                    mutation: Mutation::ToggleEnabled {
+                        group: group.clone(),
+                        rule_type: rule_type.clone(),
+                    },
+                },
+                ScheduledMutation {
+                    at: noon,
+                    mutation: Mutation::ToggleEnforce {
+                        group,
+                        rule_type,
+                        rule: 7,
+                    },
+                },
+            ],
+            ..Default::default()
+        };
+
+        let report = chaos(service, &workload, &options).await.unwrap();
+
+        assert!(report.is_consistent(), "{report}");
+        assert_eq!(report.unverified, 0);
+        assert_eq!(report.revisions, vec![1, 2]);
+        assert_eq!(report.mutations[0].revision, Some(2));
+        assert_eq!(
+            report.mutations[1].error.as_deref(),
+            Some("Shift/SideJobPrevention has no Rule[7]")
+        );
+        let decisions = &report.simulation.decisions;
+        assert!(decisions.iter().any(|d| d.at < noon && !d.allowed));
+        assert!(decisions.iter().all(|d| (d.at < noon) == (d.revision == 1)));
+        assert!(decisions.iter().filter(|d| d.at >= noon).all(|d| d.allowed));
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains(r#"Enabled="False""#));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mutation_missing_from_the_file_is_rejected() {
+        let (service, path) = scratch_service("chaos_external_edit", WatchOptions::default()).await;
+        // External edit that may not be reloaded yet: Rule[2] can still be served, the file lost it
+        let written = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace(r#"<Rule ForCandidateStatusIds="5" Enforce="" />"#, "");
+        std::fs::write(&path, &written).unwrap();
+        let workload = Workload::generate(&WorkloadOptions::default());
+        let (group, rule_type) = side_job();
+        let options = ChaosOptions {
+            random_mutations: 0,
+            schedule: vec![ScheduledMutation {
+                at: workload.day.and_hms_opt(12, 0, 0).unwrap(),
+                mutation: Mutation::ToggleEnforce {
+                    group,
+                    rule_type,
+                    rule: 2,
+                },
+            }],
+            ..Default::default()
+        };
+
+        let report = chaos(service, &workload, &options).await.unwrap();
+
+        assert_eq!(
+            report.mutations[0].error.as_deref(),
+            Some("Shift/SideJobPrevention has no Rule[2]")
+        );
+        assert_eq!(report.mutations[0].revision, None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_random_thresholds_near_i32_max() {
+        let config: Config = quick_xml::de::from_str(
+            r#"<config><ValidationRules><Groups>
+                  <ValidationRulesGroup Group="Shift">
+                     <ValidationRule Type="ExhaustionPrevention" Enabled="True">
+                        <Rules><Rule ForCandidateStatusIds="" HoursAllowed="2147483647" WithinXHours="2147483647" Enforce="True" /></Rules>
+                     </ValidationRule>
+                  </ValidationRulesGroup>
+               </Groups></ValidationRules></config>"#,
+        )
+        .unwrap();
+
+        for seed in 0..64 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            if let Some(mutation) = Mutation::random(&mut rng, &config) {
+                assert!(mutation.apply(&mut config.clone()).is_ok(), "{mutation:?}");
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_random_mutations_keep_decisions_consistent() {
+        let workload = Workload::generate(&WorkloadOptions::default());
+        // The day in 2 seconds, a simulated minute of debounce
+        let clock = AcceleratedClock::new(workload.day.and_time(NaiveTime::MIN), 43_200);
+        let (service, path) = scratch_service(
+            "chaos_random",
+            WatchOptions {
+                debounce: Duration::from_secs(60),
+                clock: Arc::new(clock),
+                ..Default::default()
+            },
+        )
+        .await;
+        let options = ChaosOptions {
+            seed: 7,
+            random_mutations: 8,
+            wait_for_reload: false,
+            realtime: true,
+            ..Default::default()
+        };
+
+        let report = chaos(service.clone(), &workload, &options).await.unwrap();
+
+        assert!(report.is_consistent(), "{report}");
+        assert_eq!(report.unverified, 0);
+        assert_eq!(report.mutations.len(), 8);
+        assert!(report.mutations.iter().all(|m| m.error.is_none()));
+        // Reloads landed while the day was being replayed
+        assert!(report.revisions.len() > 1, "{report}");
+        let file = ConfigFormat::Xml
+            .parse(&std::fs::read_to_string(&path).unwrap())
+            .unwrap();
+        assert_eq!(service.subscribe().borrow().config, file);
+        std::fs::remove_file(path).unwrap();
+    }
+}
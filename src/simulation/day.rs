@@ -0,0 +1,114 @@
+use crate::library::clock::{FixedClock, SharedClock};
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike};
+use std::sync::Arc;
+
+/// The simulated day, `00:00` up to (not including) `24:00`, on a library `Clock`. </br>
+/// The simulation advances the clock to each event it replays, a clock running on its own
+/// (`AcceleratedClock`) is expected to get there by itself.
+#[derive(Debug, Clone)]
+pub struct SimulatedDay {
+    start: NaiveDateTime,
+    clock: SharedClock,
+}
+
+impl SimulatedDay {
+    /// On a `FixedClock` starting at `00:00` of `day`.
+    pub fn new(day: NaiveDate) -> Self {
+        let start = day.and_time(NaiveTime::MIN);
+        Self::with_clock(day, Arc::new(FixedClock::new(start)))
+    }
+
+    /// On `clock`, shared with the services so they agree on "now".
+    pub fn with_clock(day: NaiveDate, clock: SharedClock) -> Self {
+        Self {
+            start: day.and_time(NaiveTime::MIN),
+            clock,
+        }
+    }
+
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    pub fn day(&self) -> NaiveDate {
+        self.start.date()
+    }
+
+    /// Now on the clock, clamped to the day.
+    pub fn now(&self) -> NaiveDateTime {
+        self.clock.now().clamp(self.start, self.last_instant())
+    }
+
+    /// Hour of the day (0-23) the clock is in.
+    pub fn hour(&self) -> u32 {
+        self.now().hour()
+    }
+
+    pub fn end(&self) -> NaiveDateTime {
+        self.start + TimeDelta::days(1)
+    }
+
+    /// `[start, end)` of the `hour` of the simulated day.
+    pub fn hour_range(&self, hour: u32) -> (NaiveDateTime, NaiveDateTime) {
+        let from = self.start + TimeDelta::hours(hour as i64);
+        (from, from + TimeDelta::hours(1))
+    }
+
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        self.start <= at && at < self.end()
+    }
+
+    /// Moves the clock to `at`, times past the end of the day are clamped (see `Clock::advance_to`).
+    pub fn advance_to(&self, at: NaiveDateTime) {
+        self.clock.advance_to(at.min(self.last_instant()));
+    }
+
+    fn last_instant(&self) -> NaiveDateTime {
+        self.end() - TimeDelta::nanoseconds(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::clock::AcceleratedClock;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_clock_only_moves_forward_within_the_day() {
+        let day = NaiveDate::from_ymd_opt(2025, 2, 10).unwrap();
+        let simulated = SimulatedDay::new(day);
+        assert_eq!(simulated.hour(), 0);
+
+        simulated.advance_to(day.and_hms_opt(13, 30, 0).unwrap());
+        assert_eq!(simulated.hour(), 13);
+
+        simulated.advance_to(day.and_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(simulated.now(), day.and_hms_opt(13, 30, 0).unwrap());
+
+        simulated.advance_to(day.and_hms_opt(23, 0, 0).unwrap() + TimeDelta::hours(5));
+        assert_eq!(simulated.hour(), 23);
+        assert!(simulated.contains(simulated.now()));
+        assert_eq!(
+            simulated.hour_range(23),
+            (day.and_hms_opt(23, 0, 0).unwrap(), simulated.end())
+        );
+    }
+
+    #[test]
+    fn test_running_clock_is_clamped_to_the_day() {
+        let day = NaiveDate::from_ymd_opt(2025, 2, 10).unwrap();
+        // Started the day before, a day per 10ms
+        let clock = AcceleratedClock::new(
+            day.and_hms_opt(0, 0, 0).unwrap() - TimeDelta::days(1),
+            8_640_000,
+        );
+        let simulated = SimulatedDay::with_clock(day, Arc::new(clock));
+
+        simulated.advance_to(day.and_hms_opt(12, 0, 0).unwrap());
+        assert!(simulated.contains(simulated.now()));
+        std::thread::sleep(std::time::Duration::from_millis(30));
+        assert_eq!(simulated.now(), simulated.end() - TimeDelta::nanoseconds(1));
+    }
+}
@@ -0,0 +1,315 @@
+use super::day::SimulatedDay;
+use super::report::{CandidateKey, Decision, SimulationReport};
+use crate::library::clock::SharedClock;
+use crate::library::rule_validation_error::RuleValidationError;
+use crate::library::rule_validation_service::RuleValidationService;
+use crate::library::{Action, Candidate, Result, Shift, ValidationRequest};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Something a candidate does (or that happens to their shift) during the simulated day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventKind {
+    /// Candidate books the shift, it lands on their calendar when allowed.
+    Book(Shift),
+    /// Candidate cancels a booked shift, it is removed from their calendar when allowed.
+    Cancel { shift_id: i32 },
+    /// Booked shift ended (at the event time when earlier than planned), not validated.
+    ShiftEnd { shift_id: i32, end_reason_id: i32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationEvent {
+    pub at: NaiveDateTime,
+    pub tenant_id: Option<String>,
+    pub candidate_id: i32,
+    pub kind: EventKind,
+}
+
+/// Replays a day of booking activity against `RuleValidationService`. </br>
+/// Candidates carry their calendar from event to event, so earlier bookings / cancels / shift ends
+/// of the day are what later requests are validated against.
+pub struct Simulation {
+    service: Arc<RuleValidationService>,
+    day: SimulatedDay,
+    candidates: BTreeMap<CandidateKey, Candidate>,
+}
+
+impl Simulation {
+    /// Simulates `day` on its own `FixedClock`.
+    pub fn new(service: Arc<RuleValidationService>, day: NaiveDate) -> Self {
+        Self::on(service, SimulatedDay::new(day))
+    }
+
+    /// Simulates `day` on `clock`, pass the clock of the services so they agree on "now".
+    pub fn with_clock(
+        service: Arc<RuleValidationService>,
+        day: NaiveDate,
+        clock: SharedClock,
+    ) -> Self {
+        Self::on(service, SimulatedDay::with_clock(day, clock))
+    }
+
+    fn on(service: Arc<RuleValidationService>, day: SimulatedDay) -> Self {
+        Self {
+            service,
+            day,
+            candidates: BTreeMap::new(),
+        }
+    }
+
+    /// Registers a candidate (with the shifts already on their calendar) of `tenant_id`.
+    pub fn add_candidate(&mut self, tenant_id: Option<String>, candidate: Candidate) {
+        self.candidates.insert((tenant_id, candidate.id), candidate);
+    }
+
+    pub fn candidate(&self, tenant_id: Option<&str>, candidate_id: i32) -> Option<&Candidate> {
+        self.candidates
+            .get(&(tenant_id.map(str::to_string), candidate_id))
+    }
+
+    pub fn day(&self) -> &SimulatedDay {
+        &self.day
+    }
+
+    /// Replays the events in time order, advancing the clock to each of them. Events outside of the
+    /// simulated day are ignored.
+    pub fn run(
+        &mut self,
+        events: impl IntoIterator<Item = SimulationEvent>,
+    ) -> Result<SimulationReport> {
+        let mut report = SimulationReport::new(self.day.day());
+        for (index, event) in self.timeline(events).into_iter().enumerate() {
+            self.replay(index, event, &mut report)?;
+        }
+        self.day.advance_to(self.day.end());
+
+        Ok(report)
+    }
+
+    /// The events of the simulated day in time order, `index` of a `Decision` points into it.
+    pub(crate) fn timeline(
+        &self,
+        events: impl IntoIterator<Item = SimulationEvent>,
+    ) -> Vec<SimulationEvent> {
+        let mut events: Vec<SimulationEvent> = events
+            .into_iter()
+            .filter(|event| self.day.contains(event.at))
+            .collect();
+        events.sort_by_key(|event| event.at);
+        events
+    }
+
+    /// Advances the clock to the event and replays it, returns the request when it was validated.
+    pub(crate) fn replay(
+        &mut self,
+        index: usize,
+        event: SimulationEvent,
+        report: &mut SimulationReport,
+    ) -> Result<Option<ValidationRequest>> {
+        self.day.advance_to(event.at);
+        let hour = &mut report.hours[self.day.hour() as usize];
+        let key = (event.tenant_id, event.candidate_id);
+        let Some(candidate) = self.candidates.get_mut(&key) else {
+            hour.skipped += 1;
+            return Ok(None);
+        };
+
+        let (action, shift) = match event.kind {
+            EventKind::Book(shift) => (Action::Book, shift),
+            EventKind::Cancel { shift_id } => {
+                match candidate.shifts.iter().find(|shift| shift.id == shift_id) {
+                    Some(shift) => (Action::Cancel, shift.clone()),
+                    None => {
+                        hour.skipped += 1;
+                        return Ok(None);
+                    }
+                }
+            }
+            EventKind::ShiftEnd {
+                shift_id,
+                end_reason_id,
+            } => {
+                match candidate
+                    .shifts
+                    .iter_mut()
+                    .find(|shift| shift.id == shift_id)
+                {
+                    Some(shift) => {
+                        shift.end = shift.end.min(event.at);
+                        shift.end_reason_id = Some(end_reason_id);
+                        hour.shift_ends += 1;
+                    }
+                    None => hour.skipped += 1,
+                }
+                return Ok(None);
+            }
+        };
+
+        let request = ValidationRequest {
+            tenant_id: key.0.clone(),
+            candidate: candidate.clone(),
+            shift,
+            action,
+            requested_at: self.day.now(),
+        };
+        let outcome = self.service.evaluate(&request)?;
+
+        hour.requests += 1;
+        for violation in outcome.errors.iter().chain(&outcome.warnings) {
+            hour.record_violation(&key, violation);
+        }
+        report.decisions.push(Decision {
+            index,
+            at: request.requested_at,
+            candidate: key.clone(),
+            shift_id: request.shift.id,
+            action,
+            revision: outcome.revision,
+            allowed: outcome.is_allowed(),
+            blocked_by: rule_names(&outcome.errors),
+            warned_by: rule_names(&outcome.warnings),
+        });
+        if !outcome.is_allowed() {
+            hour.blocked += 1;
+            return Ok(Some(request));
+        }
+
+        hour.allowed += 1;
+        match action {
+            Action::Book => candidate.shifts.push(request.shift.clone()),
+            Action::Cancel => candidate
+                .shifts
+                .retain(|shift| shift.id != request.shift.id),
+        }
+        Ok(Some(request))
+    }
+}
+
+pub(crate) fn rule_names(violations: &[RuleValidationError]) -> Vec<String> {
+    violations
+        .iter()
+        .map(|violation| violation.rule_name().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::fixtures::{at, shift};
+    use crate::library::clock::FixedClock;
+    use crate::library::configuration_service::ConfigurationService;
+    use chrono::TimeDelta;
+    use pretty_assertions::assert_eq;
+
+    fn event(at: NaiveDateTime, candidate_id: i32, kind: EventKind) -> SimulationEvent {
+        SimulationEvent {
+            at,
+            tenant_id: None,
+            candidate_id,
+            kind,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_day_is_replayed_against_the_rules() {
+        let config_service = ConfigurationService::new("test_config.xml".to_string()).await;
+        let service = RuleValidationService::new(config_service).await;
+        let mut simulation = Simulation::new(service, at(10, 0).date());
+        for id in [7, 8] {
+            simulation.add_candidate(
+                None,
+                Candidate {
+                    id,
+                    status_id: 1,
+                    shifts: vec![],
+                },
+            );
+        }
+        let tomorrow = at(10, 8) + TimeDelta::days(1);
+
+        let report = simulation
+            .run(vec![
+                // Second booking overlaps the first one only if that one is still on the calendar
+                event(
+                    at(10, 9) + TimeDelta::minutes(15),
+                    7,
+                    EventKind::Book(shift(1, tomorrow, 8)),
+                ),
+                event(
+                    at(10, 9) + TimeDelta::minutes(45),
+                    7,
+                    EventKind::Book(shift(2, tomorrow + TimeDelta::hours(4), 8)),
+                ),
+                event(at(10, 14), 7, EventKind::Cancel { shift_id: 1 }),
+                event(
+                    at(10, 15),
+                    7,
+                    EventKind::Book(shift(2, tomorrow + TimeDelta::hours(4), 8)),
+                ),
+                event(
+                    at(10, 9) + TimeDelta::minutes(30),
+                    8,
+                    EventKind::Book(shift(3, tomorrow, 8)),
+                ),
+                event(at(10, 20), 9, EventKind::Cancel { shift_id: 5 }),
+                event(
+                    at(10, 8) - TimeDelta::days(1),
+                    8,
+                    EventKind::Cancel { shift_id: 3 },
+                ),
+            ])
+            .unwrap();
+
+        let nine = &report.hours[9];
+        assert_eq!((nine.requests, nine.allowed, nine.blocked), (3, 2, 1));
+        assert_eq!(nine.rules["SideJobPrevention"].errors, 1);
+        assert_eq!(
+            nine.rules["SideJobPrevention"].candidates,
+            BTreeMap::from([((None, 7), 1)])
+        );
+        assert_eq!(report.hours[14].allowed, 1);
+        assert_eq!(report.hours[15].allowed, 1);
+        assert_eq!(report.hours[20].skipped, 1);
+
+        let totals = report.totals();
+        assert_eq!((totals.requests, totals.blocked, totals.skipped), (5, 1, 1));
+        assert_eq!(
+            simulation
+                .candidate(None, 7)
+                .unwrap()
+                .shifts
+                .iter()
+                .map(|s| s.id)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(simulation.day().hour(), 23);
+    }
+
+    #[tokio::test]
+    async fn test_services_share_the_simulated_time() {
+        let clock: SharedClock = Arc::new(FixedClock::new(at(10, 0)));
+        let watch_options = crate::library::file_watcher::WatchOptions {
+            clock: clock.clone(),
+            ..Default::default()
+        };
+        let config_service =
+            ConfigurationService::with_options("test_config.xml".to_string(), watch_options).await;
+        let service = RuleValidationService::new(config_service).await;
+        let mut simulation = Simulation::with_clock(service.clone(), at(10, 0).date(), clock);
+
+        simulation
+            .day()
+            .advance_to(at(10, 9) + TimeDelta::minutes(30));
+        assert_eq!(service.clock().now(), at(10, 9) + TimeDelta::minutes(30));
+
+        simulation.run(Vec::new()).unwrap();
+        assert_eq!(
+            service.clock().now(),
+            simulation.day().end() - TimeDelta::nanoseconds(1)
+        );
+    }
+}
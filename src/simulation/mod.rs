@@ -0,0 +1,14 @@
+pub mod chaos;
+pub mod day;
+pub mod engine;
+pub mod report;
+pub mod what_if;
+pub mod workload;
+
+//Flatten the module structure
+pub use chaos::{chaos, AppliedMutation, ChaosOptions, ChaosReport, Mutation, ScheduledMutation};
+pub use day::SimulatedDay;
+pub use engine::{EventKind, Simulation, SimulationEvent};
+pub use report::{CandidateKey, Decision, HourReport, RuleFirings, SimulationReport};
+pub use what_if::{compare, Flip, FlipCounts, NamedConfig, WhatIfReport};
+pub use workload::{Weighted, Workload, WorkloadOptions};
@@ -0,0 +1,211 @@
+use crate::library::rule_validation_error::RuleValidationError;
+use crate::library::Action;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Tenant (`None` for the single config) and candidate id.
+pub type CandidateKey = (Option<String>, i32);
+
+/// How often a rule fired and for which candidates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleFirings {
+    /// Enforced violations, the action was blocked
+    pub errors: usize,
+    /// Violations of rules with `Enforce="False"`, the action went through
+    pub warnings: usize,
+    /// Number of firings per candidate
+    pub candidates: BTreeMap<CandidateKey, usize>,
+}
+
+impl RuleFirings {
+    fn merge(&mut self, other: &RuleFirings) {
+        self.errors += other.errors;
+        self.warnings += other.warnings;
+        for (candidate, count) in &other.candidates {
+            *self.candidates.entry(candidate.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Outcome of one book / cancel request of the simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    /// Position of the event in the time ordered event stream
+    pub index: usize,
+    pub at: NaiveDateTime,
+    pub candidate: CandidateKey,
+    pub shift_id: i32,
+    pub action: Action,
+    /// Config revision the request was evaluated against
+    pub revision: u64,
+    pub allowed: bool,
+    /// Rules with enforced violations, empty when allowed
+    pub blocked_by: Vec<String>,
+    pub warned_by: Vec<String>,
+}
+
+/// Activity of one simulated hour.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HourReport {
+    pub hour: u32,
+    /// Book / cancel requests sent to `RuleValidationService`
+    pub requests: usize,
+    pub allowed: usize,
+    pub blocked: usize,
+    pub shift_ends: usize,
+    /// Events that could not be replayed (unknown candidate or shift)
+    pub skipped: usize,
+    /// Keyed by rule name (`RuleType` as written in the config)
+    pub rules: BTreeMap<String, RuleFirings>,
+}
+
+impl HourReport {
+    pub(crate) fn record_violation(
+        &mut self,
+        candidate: &CandidateKey,
+        violation: &RuleValidationError,
+    ) {
+        let firings = self
+            .rules
+            .entry(violation.rule_name().to_string())
+            .or_default();
+        if violation.success() {
+            firings.warnings += 1;
+        } else {
+            firings.errors += 1;
+        }
+        *firings.candidates.entry(candidate.clone()).or_default() += 1;
+    }
+
+    fn merge(&mut self, other: &HourReport) {
+        self.requests += other.requests;
+        self.allowed += other.allowed;
+        self.blocked += other.blocked;
+        self.shift_ends += other.shift_ends;
+        self.skipped += other.skipped;
+        for (rule, firings) in &other.rules {
+            self.rules.entry(rule.clone()).or_default().merge(firings);
+        }
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.requests == 0 && self.shift_ends == 0 && self.skipped == 0
+    }
+}
+
+/// Per hour outcome of `Simulation::run`, always 24 entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub day: NaiveDate,
+    pub hours: Vec<HourReport>,
+    /// Every request in time order
+    pub decisions: Vec<Decision>,
+}
+
+impl SimulationReport {
+    pub fn new(day: NaiveDate) -> Self {
+        Self {
+            day,
+            hours: (0..24)
+                .map(|hour| HourReport {
+                    hour,
+                    ..Default::default()
+                })
+                .collect(),
+            decisions: Vec::new(),
+        }
+    }
+
+    /// The whole day summed up, `hour` of the result is meaningless.
+    pub fn totals(&self) -> HourReport {
+        self.hours
+            .iter()
+            .fold(HourReport::default(), |mut totals, hour| {
+                totals.merge(hour);
+                totals
+            })
+    }
+}
+
+impl fmt::Display for RuleFirings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let candidates: Vec<String> = self
+            .candidates
+            .iter()
+            .map(|((tenant_id, id), count)| match tenant_id {
+                Some(tenant_id) => format!("{tenant_id}/{id} x{count}"),
+                None => format!("{id} x{count}"),
+            })
+            .collect();
+        write!(
+            f,
+            "errors {}, warnings {}, candidates {}",
+            self.errors,
+            self.warnings,
+            candidates.join(", ")
+        )
+    }
+}
+
+/// Idle hours are left out.
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let totals = self.totals();
+        writeln!(
+            f,
+            "Simulation of {}: {} requests, {} allowed, {} blocked, {} shift ends, {} skipped",
+            self.day,
+            totals.requests,
+            totals.allowed,
+            totals.blocked,
+            totals.shift_ends,
+            totals.skipped
+        )?;
+        for hour in self.hours.iter().filter(|hour| !hour.is_idle()) {
+            writeln!(
+                f,
+                "{:02}:00  requests {}, allowed {}, blocked {}, shift ends {}, skipped {}",
+                hour.hour, hour.requests, hour.allowed, hour.blocked, hour.shift_ends, hour.skipped
+            )?;
+            for (rule, firings) in &hour.rules {
+                writeln!(f, "       {rule}: {firings}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::RuleType;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_report_display_skips_idle_hours() {
+        let mut report = SimulationReport::new(NaiveDate::from_ymd_opt(2025, 2, 10).unwrap());
+        let violation = |enforced| {
+            RuleValidationError::new(
+                &RuleType::SideJobPrevention,
+                "Overlaps".to_string(),
+                "CandidateId 7 / ShiftId 2".to_string(),
+                enforced,
+            )
+        };
+        let hour = &mut report.hours[9];
+        hour.requests = 2;
+        hour.allowed = 1;
+        hour.blocked = 1;
+        hour.record_violation(&(None, 7), &violation(true));
+        hour.record_violation(&(Some("office_b".to_string()), 7), &violation(false));
+
+        assert_eq!(
+            report.to_string(),
+            "Simulation of 2025-02-10: 2 requests, 1 allowed, 1 blocked, 0 shift ends, 0 skipped\n\
+             09:00  requests 2, allowed 1, blocked 1, shift ends 0, skipped 0\n       \
+             SideJobPrevention: errors 1, warnings 1, candidates 7 x1, office_b/7 x1\n"
+        );
+    }
+}
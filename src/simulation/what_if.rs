@@ -0,0 +1,257 @@
+use super::engine::Simulation;
+use super::report::{CandidateKey, Decision, SimulationReport};
+use super::workload::Workload;
+use crate::config::prelude::Config;
+use crate::library::rule_validation_service::RuleValidationService;
+use crate::library::{Action, Result, RuleType};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A config to compare and where it came from.
+#[derive(Debug, Clone)]
+pub struct NamedConfig {
+    pub source: PathBuf,
+    pub config: Config,
+}
+
+/// Decision that differs between the baseline and the candidate config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flip {
+    pub index: usize,
+    pub at: NaiveDateTime,
+    pub candidate: CandidateKey,
+    pub shift_id: i32,
+    pub action: Action,
+    /// `true` allowed -> blocked, `false` blocked -> allowed
+    pub now_blocked: bool,
+    /// Rules blocking on the side where the action is blocked
+    pub rules: Vec<String>,
+}
+
+/// Flips of one rule, a flip blocked by several rules counts for each of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlipCounts {
+    pub newly_blocked: usize,
+    pub newly_allowed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhatIfReport {
+    pub day: NaiveDate,
+    pub baseline_source: PathBuf,
+    pub candidate_source: PathBuf,
+    pub baseline: SimulationReport,
+    pub candidate: SimulationReport,
+    /// Requests decided in both runs
+    pub compared: usize,
+    /// Events that were a request in one run only, the calendars diverged after an earlier flip
+    /// (e.g. cancelling a booking that was blocked)
+    pub unmatched: usize,
+    pub flips: Vec<Flip>,
+    /// Keyed by (`RuleType::group`, rule name)
+    pub by_rule: BTreeMap<(String, String), FlipCounts>,
+}
+
+impl WhatIfReport {
+    pub fn newly_blocked(&self) -> usize {
+        self.flips.iter().filter(|flip| flip.now_blocked).count()
+    }
+
+    pub fn newly_allowed(&self) -> usize {
+        self.flips.iter().filter(|flip| !flip.now_blocked).count()
+    }
+}
+
+/// Replays the identical workload against both configs and reports every decision that flips. </br>
+/// Both runs start from the same calendars, each run then updates them with its own decisions.
+/// The workload runs as a single (non tenant) rule set, its `tenant_id` is ignored.
+pub fn compare(
+    baseline: NamedConfig,
+    candidate: NamedConfig,
+    workload: &Workload,
+) -> Result<WhatIfReport> {
+    let run = |named: NamedConfig| {
+        let service = RuleValidationService::for_config(named.source, named.config)?;
+        let mut simulation = Simulation::new(service, workload.day);
+        for candidate in &workload.candidates {
+            simulation.add_candidate(None, candidate.clone());
+        }
+        simulation.run(workload.events.iter().cloned().map(|mut event| {
+            event.tenant_id = None;
+            event
+        }))
+    };
+    let (baseline_source, candidate_source) = (baseline.source.clone(), candidate.source.clone());
+    let baseline = run(baseline)?;
+    let candidate = run(candidate)?;
+
+    let by_index = |report: &SimulationReport| -> BTreeMap<usize, Decision> {
+        report
+            .decisions
+            .iter()
+            .map(|decision| (decision.index, decision.clone()))
+            .collect()
+    };
+    let (before, after) = (by_index(&baseline), by_index(&candidate));
+
+    let mut report = WhatIfReport {
+        day: workload.day,
+        baseline_source,
+        candidate_source,
+        compared: 0,
+        unmatched: 0,
+        flips: Vec::new(),
+        by_rule: BTreeMap::new(),
+        baseline,
+        candidate,
+    };
+    report.unmatched = before.keys().filter(|i| !after.contains_key(i)).count()
+        + after.keys().filter(|i| !before.contains_key(i)).count();
+
+    for (index, old) in &before {
+        let Some(new) = after.get(index) else {
+            continue;
+        };
+        report.compared += 1;
+        if old.allowed == new.allowed {
+            continue;
+        }
+
+        let blocked_side = if new.allowed { old } else { new };
+        for rule in &blocked_side.blocked_by {
+            let counts = report
+                .by_rule
+                .entry((group_of(rule), rule.clone()))
+                .or_default();
+            if new.allowed {
+                counts.newly_allowed += 1;
+            } else {
+                counts.newly_blocked += 1;
+            }
+        }
+        report.flips.push(Flip {
+            index: *index,
+            at: new.at,
+            candidate: new.candidate.clone(),
+            shift_id: new.shift_id,
+            action: new.action,
+            now_blocked: !new.allowed,
+            rules: blocked_side.blocked_by.clone(),
+        });
+    }
+
+    Ok(report)
+}
+
+fn group_of(rule_name: &str) -> String {
+    RuleType::from_str(rule_name)
+        .map(|rule_type| rule_type.group().to_string())
+        .unwrap_or_default()
+}
+
+impl fmt::Display for WhatIfReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "What-if {} -> {} on {}: {} requests compared, {} allowed -> blocked, {} blocked -> allowed, {} unmatched",
+            self.baseline_source.display(),
+            self.candidate_source.display(),
+            self.day,
+            self.compared,
+            self.newly_blocked(),
+            self.newly_allowed(),
+            self.unmatched
+        )?;
+        for ((group, rule), counts) in &self.by_rule {
+            writeln!(
+                f,
+                "   {group}/{rule}: {} newly blocked, {} newly allowed",
+                counts.newly_blocked, counts.newly_allowed
+            )?;
+        }
+        for flip in &self.flips {
+            writeln!(
+                f,
+                "   {} {:?} CandidateId {} / ShiftId {}: {} ({})",
+                flip.at.format("%H:%M:%S"),
+                flip.action,
+                flip.candidate.1,
+                flip.shift_id,
+                if flip.now_blocked {
+                    "allowed -> blocked"
+                } else {
+                    "blocked -> allowed"
+                },
+                flip.rules.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::prelude::ConfigFormat;
+    use crate::simulation::WorkloadOptions;
+    use pretty_assertions::assert_eq;
+
+    fn named(source: &str, xml: &str) -> NamedConfig {
+        NamedConfig {
+            source: PathBuf::from(source),
+            config: ConfigFormat::Xml.parse(xml).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_same_config_has_no_flips() {
+        let xml = std::fs::read_to_string("test_config.xml").unwrap();
+        let workload = Workload::generate(&WorkloadOptions::default());
+
+        let report = compare(named("a.xml", &xml), named("b.xml", &xml), &workload).unwrap();
+
+        assert_eq!(report.flips, vec![]);
+        assert_eq!(report.unmatched, 0);
+        assert_eq!(report.compared, report.baseline.decisions.len());
+    }
+
+    #[test]
+    fn test_disabling_a_rule_flips_its_blocks() {
+        let xml = std::fs::read_to_string("test_config.xml").unwrap();
+        let disabled = xml.replace(r#"Enabled="True""#, r#"Enabled="False""#);
+        let workload = Workload::generate(&WorkloadOptions::default());
+
+        let report = compare(
+            named("test_config.xml", &xml),
+            named("disabled.xml", &disabled),
+            &workload,
+        )
+        .unwrap();
+        let baseline_blocked = report
+            .baseline
+            .decisions
+            .iter()
+            .filter(|decision| !decision.allowed)
+            .count();
+
+        assert_eq!(report.newly_blocked(), 0);
+        assert!(report.newly_allowed() > 0);
+        assert!(report.newly_allowed() <= baseline_blocked);
+        assert_eq!(
+            report.by_rule.keys().collect::<Vec<_>>(),
+            vec![&("Shift".to_string(), "SideJobPrevention".to_string())]
+        );
+        assert_eq!(
+            report.by_rule[&("Shift".to_string(), "SideJobPrevention".to_string())].newly_allowed,
+            report.newly_allowed()
+        );
+        assert!(report
+            .flips
+            .iter()
+            .all(|flip| flip.rules == ["SideJobPrevention"]));
+    }
+}
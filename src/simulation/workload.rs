@@ -0,0 +1,389 @@
+use super::engine::{EventKind, Simulation, SimulationEvent};
+use crate::library::{Action, Candidate, Shift, ValidationRequest};
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Values with their relative weights, e.g. `vec![(1, 70), (2, 30)]` picks `1` 70% of the time.
+pub type Weighted<T> = Vec<(T, u32)>;
+
+/// Shape of a generated workload, `Default` is a small office day. </br>
+/// Every weighted list needs at least one non zero weight.
+#[derive(Debug, Clone)]
+pub struct WorkloadOptions {
+    /// Same seed and options always generate the same workload
+    pub seed: u64,
+    /// Simulated day the actions happen on
+    pub day: NaiveDate,
+    pub tenant_id: Option<String>,
+
+    pub candidates: usize,
+    pub candidate_status_ids: Weighted<i32>,
+    /// Shifts already on each candidate's calendar
+    pub history_shifts: RangeInclusive<usize>,
+    /// History shifts start between `history_days` before and `days_ahead` after `day`
+    pub history_days: i64,
+    /// Share of the past history shifts that ended with one of the `end_reason_ids`
+    pub ended_ratio: f64,
+    pub end_reason_ids: Weighted<i32>,
+
+    /// Open shifts candidates book during the day
+    pub open_shifts: usize,
+    /// Open shifts start between `days_ahead` days after `day`
+    pub days_ahead: RangeInclusive<i64>,
+    pub position_type_ids: Weighted<i32>,
+    pub match_status_ids: Weighted<i32>,
+    pub shift_status_ids: Weighted<i32>,
+    /// Shift start hour, late starts with long durations make overnight shifts
+    pub start_hours: Weighted<u32>,
+    pub duration_hours: Weighted<i64>,
+
+    /// Number of events during the day
+    pub actions: usize,
+    pub book_weight: u32,
+    pub cancel_weight: u32,
+    pub shift_end_weight: u32,
+    /// Relative activity of every hour of the day
+    pub hourly_activity: [u32; 24],
+}
+
+impl Default for WorkloadOptions {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            day: NaiveDate::from_ymd_opt(2025, 2, 10).unwrap(),
+            tenant_id: None,
+            candidates: 50,
+            candidate_status_ids: vec![(1, 60), (2, 15), (3, 10), (4, 10), (5, 5)],
+            history_shifts: 0..=6,
+            history_days: 14,
+            ended_ratio: 0.2,
+            end_reason_ids: vec![(1, 50), (2, 20), (3, 20), (4, 10)],
+            open_shifts: 200,
+            days_ahead: 0..=7,
+            position_type_ids: vec![(1, 60), (2, 30), (3, 10)],
+            match_status_ids: vec![(0, 70), (1, 20), (2, 10)],
+            shift_status_ids: vec![(1, 80), (2, 15), (3, 5)],
+            start_hours: vec![(6, 20), (7, 20), (8, 20), (14, 20), (18, 10), (22, 10)],
+            duration_hours: vec![(4, 15), (6, 20), (8, 50), (10, 10), (12, 5)],
+            actions: 500,
+            book_weight: 70,
+            cancel_weight: 20,
+            shift_end_weight: 10,
+            // Quiet nights, busy mornings and a smaller evening peak
+            hourly_activity: [
+                1, 1, 1, 1, 1, 2, 5, 8, 10, 10, 9, 8, 7, 7, 8, 8, 7, 6, 6, 5, 4, 3, 2, 1,
+            ],
+        }
+    }
+}
+
+/// Generated candidates, open shifts and the day of actions on them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workload {
+    pub day: NaiveDate,
+    pub tenant_id: Option<String>,
+    pub candidates: Vec<Candidate>,
+    pub open_shifts: Vec<Shift>,
+    /// In time order, cancels / shift ends refer to shifts of the candidate's calendar or booked earlier
+    pub events: Vec<SimulationEvent>,
+}
+
+impl Workload {
+    pub fn generate(options: &WorkloadOptions) -> Self {
+        let mut generator = Generator {
+            rng: ChaCha8Rng::seed_from_u64(options.seed),
+            options,
+            day_start: options.day.and_time(NaiveTime::MIN),
+            next_shift_id: 1,
+        };
+
+        let candidates: Vec<Candidate> = (1..=options.candidates as i32)
+            .map(|id| generator.candidate(id))
+            .collect();
+        let open_shifts: Vec<Shift> = (0..options.open_shifts)
+            .map(|_| {
+                let days = generator.rng.random_range(options.days_ahead.clone());
+                generator.shift(days)
+            })
+            .collect();
+        let events = generator.events(&candidates, &open_shifts);
+
+        Self {
+            day: options.day,
+            tenant_id: options.tenant_id.clone(),
+            candidates,
+            open_shifts,
+            events,
+        }
+    }
+
+    /// Registers the candidates, `Simulation::run(workload.events)` replays the day.
+    pub fn add_to(&self, simulation: &mut Simulation) {
+        for candidate in &self.candidates {
+            simulation.add_candidate(self.tenant_id.clone(), candidate.clone());
+        }
+    }
+
+    /// Every book / cancel event as a standalone request against the generated calendars,
+    /// for load tests that call `RuleValidationService::evaluate` directly.
+    pub fn requests(&self) -> Vec<ValidationRequest> {
+        let candidates: BTreeMap<i32, &Candidate> =
+            self.candidates.iter().map(|c| (c.id, c)).collect();
+        let shifts: BTreeMap<i32, &Shift> = self
+            .candidates
+            .iter()
+            .flat_map(|c| &c.shifts)
+            .chain(&self.open_shifts)
+            .map(|s| (s.id, s))
+            .collect();
+
+        self.events
+            .iter()
+            .filter_map(|event| {
+                let (action, shift) = match &event.kind {
+                    EventKind::Book(shift) => (Action::Book, shift),
+                    EventKind::Cancel { shift_id } => (Action::Cancel, *shifts.get(shift_id)?),
+                    EventKind::ShiftEnd { .. } => return None,
+                };
+                Some(ValidationRequest {
+                    tenant_id: event.tenant_id.clone(),
+                    candidate: (*candidates.get(&event.candidate_id)?).clone(),
+                    shift: shift.clone(),
+                    action,
+                    requested_at: event.at,
+                })
+            })
+            .collect()
+    }
+}
+
+struct Generator<'a> {
+    rng: ChaCha8Rng,
+    options: &'a WorkloadOptions,
+    day_start: NaiveDateTime,
+    next_shift_id: i32,
+}
+
+impl Generator<'_> {
+    fn pick<T: Copy>(&mut self, weighted: &[(T, u32)]) -> T {
+        let total: u32 = weighted.iter().map(|(_, weight)| weight).sum();
+        let mut roll = self.rng.random_range(0..total.max(1));
+        for (value, weight) in weighted {
+            if roll < *weight {
+                return *value;
+            }
+            roll -= weight;
+        }
+        panic!("Workload weights must not be empty or all zero")
+    }
+
+    /// Shift starting `days` after the simulated day.
+    fn shift(&mut self, days: i64) -> Shift {
+        let options = self.options;
+        let start = self.day_start
+            + TimeDelta::days(days)
+            + TimeDelta::hours(self.pick(&options.start_hours) as i64);
+        let id = self.next_shift_id;
+        self.next_shift_id += 1;
+
+        Shift {
+            id,
+            start,
+            end: start + TimeDelta::hours(self.pick(&options.duration_hours)),
+            position_type_id: self.pick(&options.position_type_ids),
+            match_status_id: self.pick(&options.match_status_ids),
+            shift_status_id: self.pick(&options.shift_status_ids),
+            end_reason_id: None,
+        }
+    }
+
+    /// Candidate with a calendar of non overlapping shifts, past ones may have ended with a reason.
+    fn candidate(&mut self, id: i32) -> Candidate {
+        let options = self.options;
+        let status_id = self.pick(&options.candidate_status_ids);
+        let count = self.rng.random_range(options.history_shifts.clone());
+        let mut shifts: Vec<Shift> = Vec::new();
+
+        for _ in 0..count {
+            let days = self
+                .rng
+                .random_range(-options.history_days..=*options.days_ahead.end());
+            let mut shift = self.shift(days);
+            if shifts.iter().any(|other| other.overlaps(&shift)) {
+                continue;
+            }
+            if shift.end <= self.day_start && self.rng.random_bool(options.ended_ratio) {
+                shift.end_reason_id = Some(self.pick(&options.end_reason_ids));
+            }
+            shifts.push(shift);
+        }
+        shifts.sort_by_key(|shift| shift.start);
+
+        Candidate {
+            id,
+            status_id,
+            shifts,
+        }
+    }
+
+    /// Actions spread over the day by `hourly_activity`. Cancels and shift ends pick one of the
+    /// candidate's shifts that is still ahead / running at that time, and fall back to a booking.
+    fn events(&mut self, candidates: &[Candidate], open_shifts: &[Shift]) -> Vec<SimulationEvent> {
+        let options = self.options;
+        if candidates.is_empty() || open_shifts.is_empty() {
+            return Vec::new();
+        }
+
+        let hours: Weighted<u32> = (0..24).zip(options.hourly_activity).collect();
+        let mut times: Vec<NaiveDateTime> = (0..options.actions)
+            .map(|_| {
+                let hour = self.pick(&hours) as i64;
+                let second = self.rng.random_range(0..3600);
+                self.day_start + TimeDelta::hours(hour) + TimeDelta::seconds(second)
+            })
+            .collect();
+        times.sort();
+
+        // Shifts each candidate is expected to have at that point of the day
+        let mut calendars: Vec<Vec<Shift>> = candidates.iter().map(|c| c.shifts.clone()).collect();
+        let mut events = Vec::with_capacity(times.len());
+
+        for at in times {
+            let index = self.rng.random_range(0..candidates.len());
+            let calendar = &mut calendars[index];
+            let action = self.pick(&[
+                ("book", options.book_weight),
+                ("cancel", options.cancel_weight),
+                ("shift_end", options.shift_end_weight),
+            ]);
+
+            let upcoming: Vec<usize> = (0..calendar.len())
+                .filter(|&i| calendar[i].start > at)
+                .collect();
+            let running: Vec<usize> = (0..calendar.len())
+                .filter(|&i| {
+                    let shift = &calendar[i];
+                    shift.start <= at && at < shift.end && shift.end_reason_id.is_none()
+                })
+                .collect();
+
+            let kind = match action {
+                "cancel" if !upcoming.is_empty() => {
+                    let i = upcoming[self.rng.random_range(0..upcoming.len())];
+                    EventKind::Cancel {
+                        shift_id: calendar.remove(i).id,
+                    }
+                }
+                "shift_end" if !running.is_empty() => {
+                    let i = running[self.rng.random_range(0..running.len())];
+                    let end_reason_id = self.pick(&options.end_reason_ids);
+                    calendar[i].end = at;
+                    calendar[i].end_reason_id = Some(end_reason_id);
+                    EventKind::ShiftEnd {
+                        shift_id: calendar[i].id,
+                        end_reason_id,
+                    }
+                }
+                _ => {
+                    let shift = open_shifts[self.rng.random_range(0..open_shifts.len())].clone();
+                    calendar.push(shift.clone());
+                    EventKind::Book(shift)
+                }
+            };
+
+            events.push(SimulationEvent {
+                at,
+                tenant_id: options.tenant_id.clone(),
+                candidate_id: candidates[index].id,
+                kind,
+            });
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::configuration_service::ConfigurationService;
+    use crate::library::rule_validation_service::RuleValidationService;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_same_seed_generates_same_workload() {
+        let options = WorkloadOptions::default();
+
+        assert_eq!(Workload::generate(&options), Workload::generate(&options));
+        assert_ne!(
+            Workload::generate(&options),
+            Workload::generate(&WorkloadOptions { seed: 7, ..options })
+        );
+    }
+
+    #[test]
+    fn test_generated_workload_follows_options() {
+        let options = WorkloadOptions {
+            tenant_id: Some("office_b".to_string()),
+            ..Default::default()
+        };
+        let workload = Workload::generate(&options);
+        let day_end = options.day.and_time(NaiveTime::MIN) + TimeDelta::days(1);
+
+        assert_eq!(workload.candidates.len(), options.candidates);
+        assert_eq!(workload.events.len(), options.actions);
+        for candidate in &workload.candidates {
+            assert!(options.history_shifts.contains(&candidate.shifts.len()));
+            for (i, shift) in candidate.shifts.iter().enumerate() {
+                assert!(!candidate.shifts[i + 1..].iter().any(|s| s.overlaps(shift)));
+            }
+        }
+        assert!(workload
+            .open_shifts
+            .iter()
+            .any(|shift| shift.end.date() > shift.start.date())); // overnight
+        assert!(workload
+            .events
+            .windows(2)
+            .all(|pair| pair[0].at <= pair[1].at && pair[1].at < day_end));
+        assert!(workload
+            .events
+            .iter()
+            .all(|event| event.tenant_id.as_deref() == Some("office_b")));
+
+        let kinds =
+            |f: fn(&EventKind) -> bool| workload.events.iter().filter(|e| f(&e.kind)).count();
+        assert!(
+            kinds(|k| matches!(k, EventKind::Book(_)))
+                > kinds(|k| matches!(k, EventKind::Cancel { .. }))
+        );
+        assert!(kinds(|k| matches!(k, EventKind::ShiftEnd { .. })) > 0);
+        assert_eq!(
+            workload.requests().len(),
+            options.actions - kinds(|k| matches!(k, EventKind::ShiftEnd { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_workload_drives_a_simulation() {
+        let options = WorkloadOptions::default();
+        let workload = Workload::generate(&options);
+        let config_service = ConfigurationService::new("test_config.xml".to_string()).await;
+        let service = RuleValidationService::new(config_service).await;
+
+        let mut simulation = Simulation::new(service, options.day);
+        workload.add_to(&mut simulation);
+        let totals = simulation.run(workload.events).unwrap().totals();
+
+        assert_eq!(
+            totals.requests + totals.shift_ends + totals.skipped,
+            options.actions
+        );
+        assert!(totals.blocked > 0);
+        assert!(totals.rules.contains_key("SideJobPrevention"));
+    }
+}